@@ -0,0 +1,128 @@
+//! compare the concrete iterators of Graph with the old Box<dyn Iterator> ones
+//! run with: cargo bench --bench iter
+use graph::{bfs, dijkstra, spfa, CsrGraph, Graph, VGraph, VertexId};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// what every Graph method returned before: a boxed iterator
+struct Boxed<'a, G: Graph>(&'a G);
+
+impl<'a, G: Graph> Graph for Boxed<'a, G> {
+    type Weight = G::Weight;
+    type Index = G::Index;
+    type IterVFrom<'b> = Box<dyn Iterator<Item = usize> + 'b> where Self: 'b;
+    type IterVTo<'b> = Box<dyn Iterator<Item = usize> + 'b> where Self: 'b;
+    type IterEFrom<'b> = Box<dyn Iterator<Item = (usize, G::Weight)> + 'b> where Self: 'b;
+    type IterETo<'b> = Box<dyn Iterator<Item = (usize, G::Weight)> + 'b> where Self: 'b;
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        Box::new(self.0.iter_v_from(u))
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        Box::new(self.0.iter_v_to(u))
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        Box::new(self.0.iter_e_from(u))
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        Box::new(self.0.iter_e_to(u))
+    }
+}
+
+/// xorshift, so no rand is needed
+fn random_edges(n: usize, m: usize) -> Vec<(usize, usize, u64)> {
+    let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x
+    };
+    (0..m)
+        .map(|_| {
+            let u = next() as usize % n;
+            let v = next() as usize % n;
+            (u, v, next() % 1000 + 1)
+        })
+        .collect()
+}
+
+fn bench<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    f(); // warm up
+    let rounds = 10;
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    let avg = start.elapsed() / rounds;
+    println!("{:<32}{:>12.3?}", name, avg);
+    avg
+}
+
+fn compare<G: Graph<Weight = u64>>(name: &str, graph: &G) {
+    let boxed = Boxed(graph);
+    let n = graph.len();
+    for (algo, fast, slow) in [
+        (
+            "bfs",
+            bench(&format!("{} bfs", name), || {
+                black_box(bfs::bfs(graph, 0).count());
+            }),
+            bench(&format!("{} bfs (boxed)", name), || {
+                black_box(bfs::bfs(&boxed, 0).count());
+            }),
+        ),
+        (
+            "dijkstra",
+            bench(&format!("{} dijkstra", name), || {
+                black_box(dijkstra::dijkstra(graph, 0).count());
+            }),
+            bench(&format!("{} dijkstra (boxed)", name), || {
+                black_box(dijkstra::dijkstra(&boxed, 0).count());
+            }),
+        ),
+        (
+            "spfa",
+            bench(&format!("{} spfa", name), || {
+                black_box(spfa::spfa(graph, n - 1));
+            }),
+            bench(&format!("{} spfa (boxed)", name), || {
+                black_box(spfa::spfa(&boxed, n - 1));
+            }),
+        ),
+    ] {
+        println!(
+            "{} {}: {:.2}x faster than boxed",
+            name,
+            algo,
+            slow.as_secs_f64() / fast.as_secs_f64()
+        );
+    }
+}
+
+fn main() {
+    let (n, m) = (100_000, 1_000_000);
+    let edges = random_edges(n, m);
+
+    let mut lst = vec![HashMap::new(); n];
+    for &(u, v, w) in edges.iter() {
+        lst[u].insert(v, w);
+    }
+    let vgraph = VGraph::new(lst);
+    compare("VGraph", &vgraph);
+
+    let csr = CsrGraph::from_edges(n, edges.iter().copied());
+    compare("CsrGraph", &csr);
+
+    // half the memory of vertex indices
+    let csr = CsrGraph::<_, u32>::try_from_edges(n, edges).unwrap();
+    compare("CsrGraph<u32>", &csr);
+}
@@ -1,16 +1,18 @@
-use crate::Graph;
-use utils::Heap;
+use crate::{Graph, IndexType, PathEdge, VertexId, Weight};
+use crate::heap::Heap;
 
 /// the difference between dijstra and prim's algorithm:
 /// 1. dijstra need to specify a start vertex, while prim needn't
 /// 2. the weight push to the heap: d + w vs w
 /// 3. [NOT Algorithm] dijkstra works on directed graph, while prim on undirected graph
-pub fn a_star<G, F>(graph: &G, start: usize, func: F) -> AStarIter<G, F>
+///
+/// NOTE: overflowed candidates are ignored (see dijkstra)
+pub fn a_star<G, F>(graph: &G, start: impl Into<VertexId>, func: F) -> AStarIter<'_, G, F>
 where
     G: Graph,
     F: Fn(usize) -> G::Weight,
 {
-    AStarIter::new(graph, start, func)
+    AStarIter::new(graph, start.into().index(), func)
 }
 
 pub struct AStarIter<'a, G, F>
@@ -20,7 +22,7 @@ where
 {
     graph: &'a G,
     used: Vec<bool>,
-    heap: Heap<(G::Weight, usize, usize)>,
+    heap: Heap<(G::Weight, G::Index, G::Index)>, // (estimated distance, vertex, from)
     func: F,
 }
 
@@ -32,7 +34,9 @@ where
     fn new(graph: &'a G, start: usize, func: F) -> Self {
         let mut heap = Heap::new();
         for (v, w) in graph.iter_e_from(start) {
-            heap.push((w + func(v), v, start));
+            if let Some(dv) = w.checked_add(func(v)) {
+                heap.push((dv, G::Index::new(v), G::Index::new(start)));
+            }
         }
         let mut used = vec![false; graph.len()];
         used[start] = true;
@@ -50,17 +54,21 @@ where
     G: Graph,
     F: Fn(usize) -> G::Weight,
 {
-    type Item = (G::Weight, usize, usize);
+    type Item = PathEdge<G::Weight>;
 
-    fn next(&mut self) -> Option<(G::Weight, usize, usize)> {
+    fn next(&mut self) -> Option<PathEdge<G::Weight>> {
         while let Some((d, u, v)) = self.heap.pop() {
+            let u = u.index();
             if !self.used[u] {
                 self.used[u] = true;
                 let hu = (self.func)(u);
                 for (v, w) in self.graph.iter_e_from(u) {
-                    self.heap.push((d + w - hu + (self.func)(v), v, u));
+                    // d - hu is the real distance of u
+                    if let Some(dv) = (d - hu).checked_add(w).and_then(|x| x.checked_add((self.func)(v))) {
+                        self.heap.push((dv, G::Index::new(v), G::Index::new(u)));
+                    }
                 }
-                return Some((d - hu, u, v));
+                return Some(PathEdge::new(v.index(), u, d - hu));
             }
         }
         None
@@ -82,10 +90,10 @@ mod tests {
             ((x1 - x0).powi(2) + (y1 - y0).powi(2)).powf(0.5)
         };
 
-        for (w, u, v) in g.a_star(0, calc_dist_to_t) {
+        for e in g.a_star(0, calc_dist_to_t) {
             println!(
                 "to: {}, directly from: {}, distance: {:.1}",
-                s_lst[u], s_lst[v], w
+                s_lst[e.to.index()], s_lst[e.from.index()], e.dist
             );
         }
     }
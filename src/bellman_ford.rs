@@ -1,47 +1,79 @@
-use crate::Graph;
+use crate::view::MapWeight;
+use crate::semiring::{solve_bellman_ford, MinPlus};
+use crate::{Graph, GraphError, VertexId, Weight};
 
 /// return (no negative cycle?, dist, from)
 /// O(VE)
 /// The correctness of bellman ford: the count of edges of the shortestpath
 /// from one vertex to another is at most V - 1,
 /// and we need one more time to check if negative cycle exist
+/// NOTE: overflowed candidates are ignored, so a vertex reached only by overflowed paths
+/// is unreachable (None), use try_bellman_ford to get an error instead
 pub fn bellman_ford<G: Graph>(
     graph: &G,
-    start: usize,
-) -> (bool, Vec<Option<G::Weight>>, Vec<usize>) {
-    let n = graph.len();
-
-    let mut dist = vec![None; n];
-    dist[start] = Some(Default::default());
-    let mut from = vec![start; n];
-
-    for _ in 0..n {
-        let mut improved = false;
-        for u in 0..n {
-            for (v, w) in graph.iter_e_from(u) {
-                if let Some(d) = dist[u] {
-                    let can_improve = match dist[v] {
-                        None => true,
-                        Some(d0) => d + w < d0,
-                    };
-                    if can_improve {
-                        from[v] = u;
-                        dist[v] = Some(d + w);
-                        improved = true;
-                    }
-                }
-            }
-        }
-        if !improved {
-            return (true, dist, from);
-        }
+    start: impl Into<VertexId>,
+) -> (bool, Vec<Option<G::Weight>>, Vec<VertexId>) {
+    let start = start.into().index();
+    let (state, dist, from, _overflowed) = bellman_ford_checked(graph, start);
+    (state, dist, from)
+}
+
+/// return Err if `start` is out of range, negative cycle existed or some distance overflowed
+/// (an overflowed path which is not the shortest is fine)
+#[allow(clippy::type_complexity)]
+pub fn try_bellman_ford<G: Graph>(
+    graph: &G,
+    start: impl Into<VertexId>,
+) -> Result<(Vec<Option<G::Weight>>, Vec<VertexId>), GraphError> {
+    let start = start.into().index();
+    graph.check_vertex(start)?;
+    match bellman_ford_checked(graph, start) {
+        (false, _, _, _) => Err(GraphError::NegativeCycle),
+        (true, _, _, true) => Err(GraphError::Overflow),
+        (true, dist, from, false) => Ok((dist, from)),
     }
-    (false, dist, from)
+}
+
+/// distances are accumulated in D rather than G::Weight (see dijkstra_as)
+pub fn bellman_ford_as<G, D>(graph: &G, start: impl Into<VertexId>) -> (bool, Vec<Option<D>>, Vec<VertexId>)
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    let start = start.into().index();
+    bellman_ford(&MapWeight::new(graph, |_, _, w| D::from(w)), start)
+}
+
+#[allow(clippy::type_complexity)]
+pub fn try_bellman_ford_as<G, D>(
+    graph: &G,
+    start: impl Into<VertexId>,
+) -> Result<(Vec<Option<D>>, Vec<VertexId>), GraphError>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    let start = start.into().index();
+    try_bellman_ford(&MapWeight::new(graph, |_, _, w| D::from(w)), start)
+}
+
+/// return (no negative cycle?, dist, from, overflowed?), see OverflowTracker
+/// it's semiring_bellman_ford over MinPlus
+#[allow(clippy::type_complexity)]
+fn bellman_ford_checked<G: Graph>(
+    graph: &G,
+    start: usize,
+) -> (bool, Vec<Option<G::Weight>>, Vec<VertexId>, bool) {
+    let (state, value, from, overflowed) = solve_bellman_ford(graph, start, |_, _, w| MinPlus(w));
+    let dist = value.into_iter().map(|x| x.map(|x| x.0)).collect();
+    (state, dist, from, overflowed)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, VGraph};
+    use std::collections::HashMap;
 
     #[test]
     fn test_bellman_ford() {
@@ -54,8 +86,48 @@ mod tests {
         for i in 0..dist.len() {
             println!(
                 "to: {}, directly from: {}, distance: {:?}",
-                s_lst[i], s_lst[from[i]], dist[i]
+                s_lst[i], s_lst[from[i].index()], dist[i]
             )
         }
     }
+
+    #[test]
+    fn test_try_bellman_ford() {
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 1)]),
+            HashMap::from([(2, -3)]),
+            HashMap::from([(1, 1)]),
+        ]);
+        assert_eq!(try_bellman_ford(&g, 0), Err(GraphError::NegativeCycle));
+        assert!(try_bellman_ford(&g, 3).is_err());
+        let (g, _s_lst) = MakeGraph::spn();
+        assert!(try_bellman_ford(&g, 0).is_ok());
+
+        // 0 -> 1 -> 2: 200 + 100 can not be held by u8
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 200u8)]),
+            HashMap::from([(2, 100)]),
+            HashMap::new(),
+        ]);
+        assert_eq!(try_bellman_ford(&g, 0), Err(GraphError::Overflow));
+        assert_eq!(bellman_ford(&g, 0).1[2], None);
+        assert_eq!(try_bellman_ford_as::<_, u16>(&g, 0).unwrap().0[2], Some(300));
+        assert_eq!(bellman_ford_as::<_, i64>(&g, 0).1, vec![Some(0), Some(200), Some(300)]);
+
+        // 0 -> 1 -> 3 overflowed, but it's not the shortest path 0 -> 2 -> 1 -> 3
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 200u8), (2, 10)]),
+            HashMap::from([(3, 100)]),
+            HashMap::from([(1, 10)]),
+            HashMap::new(),
+        ]);
+        let dist = vec![Some(0), Some(20), Some(10), Some(120)];
+        assert_eq!(try_bellman_ford(&g, 0).unwrap().0, dist);
+        assert_eq!(bellman_ford(&g, 0).1, dist);
+        // a distance of exactly u8::MAX is not overflowed
+        let g = VGraph::new(vec![HashMap::from([(1, 200u8)]), HashMap::from([(2, 55)]), HashMap::new()]);
+        assert_eq!(try_bellman_ford(&g, 0).unwrap().0[2], Some(u8::MAX));
+        let g = VGraph::new(vec![HashMap::from([(1, -100i8)]), HashMap::from([(2, -100)]), HashMap::new()]);
+        assert_eq!(try_bellman_ford(&g, 0), Err(GraphError::Overflow));
+    }
 }
@@ -1,14 +1,15 @@
-use crate::Graph;
+use crate::{Graph, IndexType, VertexId};
 use std::collections::VecDeque;
 
-pub fn bfs<G: Graph>(graph: &G, start: usize) -> impl Iterator<Item = usize> + '_ {
+pub fn bfs<G: Graph>(graph: &G, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
+    let start = start.into().index();
     BfsIter::new(graph, start)
 }
 
 /// bfs helper
 struct BfsIter<'a, G: Graph> {
     visited: Vec<bool>,
-    queue: VecDeque<usize>,
+    queue: VecDeque<G::Index>,
     graph: &'a G,
 }
 
@@ -17,7 +18,7 @@ impl<'a, G: Graph> BfsIter<'a, G> {
         let mut visited = vec![false; graph.len()];
         visited[start] = true;
         let mut queue = VecDeque::new();
-        queue.push_back(start);
+        queue.push_back(G::Index::new(start));
         Self {
             visited,
             queue,
@@ -31,10 +32,11 @@ impl<'a, G: Graph> Iterator for BfsIter<'a, G> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(u) = self.queue.pop_front() {
+            let u = u.index();
             for v in self.graph.iter_v_from(u) {
                 if !self.visited[v] {
                     self.visited[v] = true;
-                    self.queue.push_back(v);
+                    self.queue.push_back(G::Index::new(v));
                 }
             }
             Some(u)
@@ -0,0 +1,253 @@
+/// build VGraph, UGraph or CsrGraph from a list of edges (by index or by label),
+/// with explicit policies for duplicated edges, self-loops and reverse edges,
+/// every index (and label) is validated when building, rather than panic in the middle
+use crate::error::check_vertex;
+use crate::{CsrGraph, Graph, GraphError, SortedVGraph, UGraph, VGraph, Weight};
+use std::collections::{BTreeMap, HashMap};
+
+/// what to do if an edge u -> v is given more than once
+/// (u - v and v - u are the same edge of UGraph)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Duplicate {
+    KeepFirst,
+    /// the same as HashMap::insert
+    #[default]
+    KeepLast,
+    KeepMin,
+    /// Err(Overflow) if the sum overflowed
+    Sum,
+    /// Err(DuplicateEdge)
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelfLoop {
+    #[default]
+    Allow,
+    /// Err(SelfLoop)
+    Reject,
+}
+
+/// which edges are added for a given edge u -> v
+/// NOTE: build_ugraph ignores it, an undirected edge has no direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// u -> v
+    #[default]
+    Forward,
+    /// v -> u, e.g. the transpose of a graph
+    Backward,
+    /// u -> v and v -> u
+    Both,
+}
+
+pub struct GraphBuilder<W: Weight> {
+    n: usize,
+    labels: Vec<String>,
+    edges: Vec<(usize, usize, W)>,
+    labeled_edges: Vec<(String, String, W)>,
+    duplicate: Duplicate,
+    self_loop: SelfLoop,
+    direction: Direction,
+}
+
+impl<W: Weight> GraphBuilder<W> {
+    /// a graph of `n` vertices
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            labels: vec![],
+            edges: vec![],
+            labeled_edges: vec![],
+            duplicate: Duplicate::default(),
+            self_loop: SelfLoop::default(),
+            direction: Direction::default(),
+        }
+    }
+
+    /// a graph whose ith vertex is labeled labels[i], so labeled_edges can be used
+    pub fn with_labels(labels: &[&str]) -> Self {
+        let mut res = Self::new(labels.len());
+        res.labels = labels.iter().map(|s| s.to_string()).collect();
+        res
+    }
+
+    /// start with all the edges of `graph`
+    pub fn from_graph<G: Graph<Weight = W>>(graph: &G) -> Self {
+        Self::new(graph.len()).edges(graph.iter_e_all())
+    }
+
+    pub fn duplicate(mut self, policy: Duplicate) -> Self {
+        self.duplicate = policy;
+        self
+    }
+
+    pub fn self_loop(mut self, policy: SelfLoop) -> Self {
+        self.self_loop = policy;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn edge(mut self, u: usize, v: usize, w: W) -> Self {
+        self.edges.push((u, v, w));
+        self
+    }
+
+    pub fn edges<I>(mut self, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize, W)>,
+    {
+        self.edges.extend(edges);
+        self
+    }
+
+    /// edges (from, to, w) by labels, see with_labels
+    pub fn labeled_edges<'a, I>(mut self, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str, W)>,
+    {
+        self.labeled_edges
+            .extend(edges.into_iter().map(|(u, v, w)| (u.to_string(), v.to_string(), w)));
+        self
+    }
+
+    /// the edges after applying all the policies, in the order they are first given:
+    /// (number of vertices, edges)
+    #[allow(clippy::type_complexity)]
+    pub fn build_edges(self) -> Result<(usize, Vec<(usize, usize, W)>), GraphError> {
+        let n = self.n;
+        Ok((n, self.resolve(false)?))
+    }
+
+    pub fn build_vgraph(self) -> Result<VGraph<W>, GraphError> {
+        let (n, edges) = self.build_edges()?;
+        let mut lst = vec![HashMap::new(); n];
+        for (u, v, w) in edges {
+            lst[u].insert(v, w);
+        }
+        Ok(VGraph::new(lst))
+    }
+
+    /// see SortedVGraph
+    pub fn build_sorted_vgraph(self) -> Result<SortedVGraph<W>, GraphError> {
+        let (n, edges) = self.build_edges()?;
+        let mut lst = vec![BTreeMap::new(); n];
+        for (u, v, w) in edges {
+            lst[u].insert(v, w);
+        }
+        Ok(SortedVGraph::sorted(lst))
+    }
+
+    pub fn build_ugraph(self) -> Result<UGraph<W>, GraphError> {
+        let n = self.n;
+        UGraph::from_edges(n, self.resolve(true)?)
+    }
+
+    pub fn build_csr(self) -> Result<CsrGraph<W>, GraphError> {
+        let (n, edges) = self.build_edges()?;
+        Ok(CsrGraph::from_edges(n, edges))
+    }
+
+    /// undirected: u - v and v - u are the same edge, and direction is ignored
+    fn resolve(self, undirected: bool) -> Result<Vec<(usize, usize, W)>, GraphError> {
+        let mut index = HashMap::new(); // label -> vertex
+        for (i, label) in self.labels.iter().enumerate() {
+            if index.insert(label.as_str(), i).is_some() {
+                return Err(GraphError::DuplicateLabel(label.clone()));
+            }
+        }
+        let find = |label: &str| match index.get(label) {
+            Some(&i) => Ok(i),
+            None => Err(GraphError::UnknownLabel(label.to_string())),
+        };
+        let mut given = self.edges;
+        for (u, v, w) in self.labeled_edges.iter() {
+            given.push((find(u)?, find(v)?, *w));
+        }
+
+        let mut res: Vec<(usize, usize, W)> = vec![];
+        let mut pos = HashMap::new(); // (u, v) -> index in res
+        for (u, v, w) in given {
+            check_vertex(u, self.n)?;
+            check_vertex(v, self.n)?;
+            if u == v && self.self_loop == SelfLoop::Reject {
+                return Err(GraphError::SelfLoop(u));
+            }
+            let lst = match (undirected, self.direction) {
+                (true, _) | (false, Direction::Forward) => vec![(u, v)],
+                (false, Direction::Backward) => vec![(v, u)],
+                (false, Direction::Both) if u == v => vec![(u, v)],
+                (false, Direction::Both) => vec![(u, v), (v, u)],
+            };
+            for (a, b) in lst {
+                let key = if undirected && a > b { (b, a) } else { (a, b) };
+                match pos.get(&key) {
+                    None => {
+                        pos.insert(key, res.len());
+                        res.push((a, b, w));
+                    }
+                    Some(&i) => {
+                        let old = res[i].2;
+                        res[i].2 = match self.duplicate {
+                            Duplicate::KeepFirst => old,
+                            Duplicate::KeepLast => w,
+                            Duplicate::KeepMin => old.min(w),
+                            Duplicate::Sum => old.checked_add(w).ok_or(GraphError::Overflow)?,
+                            Duplicate::Error => return Err(GraphError::DuplicateEdge { from: a, to: b }),
+                        };
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kruskal, MakeGraph};
+
+    #[test]
+    fn test_builder() {
+        let edges = [(0, 1, 5), (1, 2, 3), (0, 1, 2), (1, 0, 4), (2, 2, 1)];
+        let build = |policy| GraphBuilder::new(3).edges(edges).duplicate(policy).build_vgraph();
+        assert_eq!(build(Duplicate::KeepFirst).unwrap().try_weight(0, 1), Ok(Some(5)));
+        assert_eq!(build(Duplicate::KeepLast).unwrap().try_weight(0, 1), Ok(Some(2)));
+        assert_eq!(build(Duplicate::KeepMin).unwrap().try_weight(0, 1), Ok(Some(2)));
+        assert_eq!(build(Duplicate::Sum).unwrap().try_weight(0, 1), Ok(Some(7)));
+        assert!(matches!(build(Duplicate::Error), Err(GraphError::DuplicateEdge { from: 0, to: 1 })));
+
+        let b = GraphBuilder::new(3).edges(edges).self_loop(SelfLoop::Reject);
+        assert_eq!(b.build_vgraph().err(), Some(GraphError::SelfLoop(2)));
+        let b = GraphBuilder::new(2).edge(0, 2, 1);
+        assert!(matches!(b.build_csr(), Err(GraphError::VertexOutOfRange { index: 2, len: 2 })));
+
+        // u - v and v - u are the same undirected edge
+        let g = GraphBuilder::new(3).edges(edges).duplicate(Duplicate::Sum).build_ugraph().unwrap();
+        assert_eq!(g.iter_undirected_edges().count(), 3);
+        assert_eq!(g.iter_e_from(0).collect::<Vec<_>>(), vec![(1, 11)]);
+
+        // transpose
+        let (g, _s_lst) = MakeGraph::spn();
+        let rev = GraphBuilder::from_graph(&g).direction(Direction::Backward).build_vgraph().unwrap();
+        for u in 0..g.len() {
+            for (v, w) in g.iter_e_from(u) {
+                assert_eq!(rev.try_weight(v, u), Ok(Some(w)));
+            }
+        }
+
+        let b = GraphBuilder::with_labels(&["a", "b", "c"])
+            .labeled_edges([("a", "b", 1), ("b", "c", 2)])
+            .direction(Direction::Both);
+        let csr = b.build_csr().unwrap();
+        assert_eq!(kruskal::kruskal(&csr).iter().map(|e| e.weight).sum::<i32>(), 3);
+        assert_eq!(csr.iter_v_to(1).count(), 2);
+        let b = GraphBuilder::<i32>::with_labels(&["a", "b"]).labeled_edges([("a", "z", 1)]);
+        assert_eq!(b.build_edges().err(), Some(GraphError::UnknownLabel("z".to_string())));
+    }
+}
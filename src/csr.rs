@@ -0,0 +1,257 @@
+/// Compressed Sparse Row graph, it can not be modified after built
+/// all the out edges of `u` are stored contiguously:
+/// out_v[out_offset[u]..out_offset[u + 1]] (and the same index range of out_w),
+/// in edges are stored in the same way, so iter_*_from and iter_*_to are both O(degree)
+use crate::graph::Vertices;
+use crate::index::check_len;
+use crate::{Graph, GraphError, IndexType, VGraph, VertexId, Weight};
+use std::iter::{Copied, Zip};
+use std::slice::Iter;
+
+/// Ix: the type out_v/in_v are stored in, e.g. CsrGraph<W, u32> for graphs of less than 4G vertices,
+/// offsets are always usize since there may be more edges than vertices
+pub struct CsrGraph<W: Weight, Ix: IndexType = usize> {
+    out_offset: Vec<usize>,
+    out_v: Vec<Ix>,
+    out_w: Vec<W>,
+    in_offset: Vec<usize>,
+    in_v: Vec<Ix>,
+    in_w: Vec<W>,
+}
+
+impl<W: Weight> CsrGraph<W> {
+    /// `n` is the number of vertices, every edge (u, v, w) should have u, v < n
+    /// edges of the same vertex keep their order in `edges`
+    /// NOTE: duplicated edges will not be merged
+    pub fn from_edges<I>(n: usize, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize, W)>,
+    {
+        Self::try_from_edges(n, edges).expect("usize never overflow")
+    }
+
+    /// build from any graph, only iter_e_from is used
+    pub fn from_graph<G: Graph<Weight = W>>(graph: &G) -> Self {
+        Self::try_from_graph(graph).expect("usize never overflow")
+    }
+}
+
+impl<W: Weight, Ix: IndexType> CsrGraph<W, Ix> {
+    /// the same as from_edges, for any index type,
+    /// return Err if there are more vertices than Ix can index
+    pub fn try_from_edges<I>(n: usize, edges: I) -> Result<Self, GraphError>
+    where
+        I: IntoIterator<Item = (usize, usize, W)>,
+    {
+        check_len::<Ix>(n)?;
+        let edges: Vec<(usize, usize, W)> = edges.into_iter().collect();
+        let (out_offset, out_v, out_w) = compress(n, edges.iter().cloned());
+        let (in_offset, in_v, in_w) = compress(n, edges.iter().map(|&(u, v, w)| (v, u, w)));
+        Ok(Self {
+            out_offset,
+            out_v,
+            out_w,
+            in_offset,
+            in_v,
+            in_w,
+        })
+    }
+
+    pub fn try_from_graph<G: Graph<Weight = W>>(graph: &G) -> Result<Self, GraphError> {
+        Self::try_from_edges(graph.len(), graph.iter_e_all())
+    }
+}
+
+impl<W: Weight> From<&VGraph<W>> for CsrGraph<W> {
+    fn from(graph: &VGraph<W>) -> Self {
+        Self::from_graph(graph)
+    }
+}
+
+/// serialized as {n, edges}, the offsets are rebuilt (and checked) when deserializing
+#[cfg(feature = "serde")]
+impl<W: Weight + serde::Serialize, Ix: IndexType> serde::Serialize for CsrGraph<W, Ix> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let edges: Vec<(usize, usize, W)> = self.iter_e_all().collect();
+        let mut state = serializer.serialize_struct("CsrGraph", 2)?;
+        state.serialize_field("n", &self.len())?;
+        state.serialize_field("edges", &edges)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, W, Ix> serde::Deserialize<'de> for CsrGraph<W, Ix>
+where
+    W: Weight + serde::Deserialize<'de>,
+    Ix: IndexType,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::error::check_vertex;
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct Repr<W> {
+            n: usize,
+            edges: Vec<(usize, usize, W)>,
+        }
+
+        let Repr { n, edges } = Repr::<W>::deserialize(deserializer)?;
+        for &(u, v, _) in edges.iter() {
+            check_vertex(u, n).map_err(D::Error::custom)?;
+            check_vertex(v, n).map_err(D::Error::custom)?;
+        }
+        Self::try_from_edges(n, edges).map_err(D::Error::custom)
+    }
+}
+
+/// counting sort edges by their first vertex
+#[allow(clippy::type_complexity)]
+fn compress<W: Weight, Ix: IndexType>(
+    n: usize,
+    edges: impl Iterator<Item = (usize, usize, W)> + Clone,
+) -> (Vec<usize>, Vec<Ix>, Vec<W>) {
+    let mut offset = vec![0; n + 1];
+    for (u, _, _) in edges.clone() {
+        offset[u + 1] += 1;
+    }
+    for i in 0..n {
+        offset[i + 1] += offset[i];
+    }
+
+    let m = offset[n];
+    let mut pos = offset.clone();
+    let mut v_lst = vec![Ix::default(); m];
+    let mut w_lst = vec![W::zero(); m];
+    for (u, v, w) in edges {
+        v_lst[pos[u]] = Ix::new(v);
+        w_lst[pos[u]] = w;
+        pos[u] += 1;
+    }
+    (offset, v_lst, w_lst)
+}
+
+/// (v, w) of a vertex's contiguous edges
+pub struct CsrIter<'a, W, Ix>(Zip<Iter<'a, Ix>, Copied<Iter<'a, W>>>);
+
+impl<'a, W: Copy, Ix: IndexType> Iterator for CsrIter<'a, W, Ix> {
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        self.0.next().map(|(v, w)| (v.index(), w))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<W: Weight, Ix: IndexType> Graph for CsrGraph<W, Ix> {
+    type Weight = W;
+    type Index = Ix;
+    type IterVFrom<'a> = Vertices<CsrIter<'a, W, Ix>> where Self: 'a;
+    type IterVTo<'a> = Vertices<CsrIter<'a, W, Ix>> where Self: 'a;
+    type IterEFrom<'a> = CsrIter<'a, W, Ix> where Self: 'a;
+    type IterETo<'a> = CsrIter<'a, W, Ix> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.out_offset.len() - 1
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.out_offset[u] == self.out_offset[u + 1]
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.in_offset[u] == self.in_offset[u + 1]
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        let (i, j) = (self.out_offset[u], self.out_offset[u + 1]);
+        CsrIter(self.out_v[i..j].iter().zip(self.out_w[i..j].iter().copied()))
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        let (i, j) = (self.in_offset[u], self.in_offset[u + 1]);
+        CsrIter(self.in_v[i..j].iter().zip(self.in_w[i..j].iter().copied()))
+    }
+
+    fn edge_count(&self) -> usize {
+        self.out_v.len()
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.out_offset[u + 1] - self.out_offset[u]
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.in_offset[u + 1] - self.in_offset[u]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dijkstra, dinic_new, kruskal, MakeGraph};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_csr() {
+        let (g, _s_lst) = MakeGraph::scc();
+        let csr = CsrGraph::from(&g);
+        assert_eq!(csr.len(), g.len());
+        for u in 0..g.len() {
+            let lhs: HashSet<usize> = g.iter_v_from(u).collect();
+            assert_eq!(lhs, csr.iter_v_from(u).collect());
+            let lhs: HashSet<usize> = g.iter_v_to(u).collect();
+            assert_eq!(lhs, csr.iter_v_to(u).collect());
+        }
+    }
+
+    #[test]
+    fn test_csr_algorithms() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let csr = CsrGraph::from(&g);
+
+        let res = kruskal::kruskal(&csr);
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37);
+
+        let mut lhs: Vec<(i32, usize)> = dijkstra::dijkstra(&g, 7).map(|e| (e.dist, e.to.index())).collect();
+        let mut rhs: Vec<(i32, usize)> = dijkstra::dijkstra(&csr, 7).map(|e| (e.dist, e.to.index())).collect();
+        lhs.sort();
+        rhs.sort();
+        assert_eq!(lhs, rhs);
+
+        let (g, _s_lst) = MakeGraph::mf();
+        let mut edges = vec![];
+        for u in 0..g.len() {
+            for (v, w) in g.iter_e_from(u) {
+                edges.push((u, v, w));
+            }
+        }
+        let csr = CsrGraph::from_edges(g.len(), edges);
+        let flowing = dinic_new::dinic(&csr, 0, 5);
+        assert_eq!(flowing.get(&5).unwrap().values().sum::<i32>(), 23);
+    }
+}
@@ -0,0 +1,361 @@
+/// graph whose vertices and edges carry arbitrary data (no Copy needed), e.g. road names,
+/// it has no weight itself (Graph::Weight is NoWeight, good for bfs, dfs, scc...),
+/// weighted algorithms run on a view: by(|e| e.length), so one graph can serve several cost models
+/// like EGraph, parallel edges are allowed and told apart by their EdgeId
+use crate::error::check_vertex;
+use crate::graph::Vertices;
+use crate::{bellman_ford, dijkstra, kruskal};
+use crate::{EdgeId, Graph, GraphError, NoWeight, PathEdge, TreeEdge, VertexId, Weight};
+use std::iter::Map;
+use std::slice;
+
+pub struct DataGraph<N, E> {
+    nodes: Vec<N>,
+    e_lst: Vec<(usize, usize, E)>, // all edges: (from, to, data)
+    from_v_lst: Vec<Vec<usize>>,   // all edge id from v
+    to_v_lst: Vec<Vec<usize>>,     // all edge id to v
+}
+
+impl<N, E> Default for DataGraph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> DataGraph<N, E> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            e_lst: vec![],
+            from_v_lst: vec![],
+            to_v_lst: vec![],
+        }
+    }
+
+    /// add a vertex, return its index
+    pub fn add_node(&mut self, data: N) -> usize {
+        self.nodes.push(data);
+        self.from_v_lst.push(vec![]);
+        self.to_v_lst.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    /// add edge u -> v (parallel edges are ok), return its id
+    pub fn add_edge(&mut self, u: usize, v: usize, data: E) -> Result<EdgeId, GraphError> {
+        check_vertex(u, self.len())?;
+        check_vertex(v, self.len())?;
+        let i = self.e_lst.len();
+        self.from_v_lst[u].push(i);
+        self.to_v_lst[v].push(i);
+        self.e_lst.push((u, v, data));
+        Ok(EdgeId(i))
+    }
+
+    pub fn get_node(&self, u: usize) -> &N {
+        &self.nodes[u]
+    }
+
+    pub fn get_node_mut(&mut self, u: usize) -> &mut N {
+        &mut self.nodes[u]
+    }
+
+    pub fn get_edge(&self, id: EdgeId) -> &E {
+        &self.e_lst[id.0].2
+    }
+
+    pub fn get_edge_mut(&mut self, id: EdgeId) -> &mut E {
+        &mut self.e_lst[id.0].2
+    }
+
+    /// (from, to) of an edge
+    pub fn get_endpoints(&self, id: EdgeId) -> (usize, usize) {
+        let (u, v, _) = self.e_lst[id.0];
+        (u, v)
+    }
+
+    /// iter all the edges (id, from, to, data)
+    pub fn iter_edges(&self) -> impl Iterator<Item = (EdgeId, usize, usize, &E)> + '_ {
+        self.e_lst
+            .iter()
+            .enumerate()
+            .map(|(i, (u, v, e))| (EdgeId(i), *u, *v, e))
+    }
+
+    /// iter the edges (id, to, data) from vertex `u`
+    pub fn iter_edges_from(&self, u: usize) -> impl Iterator<Item = (EdgeId, usize, &E)> + '_ {
+        self.from_v_lst[u].iter().map(|&i| (EdgeId(i), self.e_lst[i].1, &self.e_lst[i].2))
+    }
+
+    /// a weighted view, func(e) is the weight of edge e
+    pub fn by<W, F>(&self, func: F) -> ByWeight<'_, N, E, F>
+    where
+        W: Weight,
+        F: Fn(&E) -> W,
+    {
+        ByWeight { graph: self, func }
+    }
+
+    fn edge_refs(&self, u: usize, forward: bool) -> EdgeRefs<'_, E> {
+        let ids = if forward { &self.from_v_lst[u] } else { &self.to_v_lst[u] };
+        EdgeRefs {
+            ids: ids.iter(),
+            e_lst: &self.e_lst,
+            forward,
+        }
+    }
+}
+
+/// the edges (v, data) of a vertex `u`, forward: they are u -> v, else v -> u
+pub struct EdgeRefs<'a, E> {
+    ids: slice::Iter<'a, usize>,
+    e_lst: &'a [(usize, usize, E)],
+    forward: bool,
+}
+
+impl<'a, E> Iterator for EdgeRefs<'a, E> {
+    type Item = (usize, &'a E);
+
+    fn next(&mut self) -> Option<(usize, &'a E)> {
+        let (u, v, e) = &self.e_lst[*self.ids.next()?];
+        Some((if self.forward { *v } else { *u }, e))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+type NoWeightIter<'a, E> = Map<EdgeRefs<'a, E>, fn((usize, &'a E)) -> (usize, NoWeight)>;
+
+fn no_weight<E>((v, _): (usize, &E)) -> (usize, NoWeight) {
+    (v, NoWeight)
+}
+
+impl<N, E> Graph for DataGraph<N, E> {
+    type Weight = NoWeight;
+    type Index = usize;
+    type IterVFrom<'a> = Vertices<EdgeRefs<'a, E>> where Self: 'a;
+    type IterVTo<'a> = Vertices<EdgeRefs<'a, E>> where Self: 'a;
+    type IterEFrom<'a> = NoWeightIter<'a, E> where Self: 'a;
+    type IterETo<'a> = NoWeightIter<'a, E> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.edge_refs(u, true))
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.from_v_lst[u].is_empty()
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.edge_refs(u, false))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.to_v_lst[u].is_empty()
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        self.edge_refs(u, true).map(no_weight as fn((usize, &E)) -> (usize, NoWeight))
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        self.edge_refs(u, false).map(no_weight as fn((usize, &E)) -> (usize, NoWeight))
+    }
+
+    /// parallel edges are all counted
+    fn edge_count(&self) -> usize {
+        self.e_lst.len()
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.from_v_lst[u].len()
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.to_v_lst[u].len()
+    }
+}
+
+/// DataGraph weighted by func(e)
+pub struct ByWeight<'a, N, E, F> {
+    graph: &'a DataGraph<N, E>,
+    func: F,
+}
+
+impl<'a, N, E, F, W> Graph for ByWeight<'a, N, E, F>
+where
+    F: Fn(&E) -> W,
+    W: Weight,
+{
+    type Weight = W;
+    type Index = usize;
+    type IterVFrom<'b> = Vertices<EdgeRefs<'b, E>> where Self: 'b;
+    type IterVTo<'b> = Vertices<EdgeRefs<'b, E>> where Self: 'b;
+    type IterEFrom<'b> = ByWeightIter<'b, E, F> where Self: 'b;
+    type IterETo<'b> = ByWeightIter<'b, E, F> where Self: 'b;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        self.graph.iter_v_from(u)
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_from(u)
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        self.graph.iter_v_to(u)
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        self.graph.has_fast_iter_to()
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_to(u)
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        ByWeightIter {
+            iter: self.graph.edge_refs(u, true),
+            func: &self.func,
+        }
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        ByWeightIter {
+            iter: self.graph.edge_refs(u, false),
+            func: &self.func,
+        }
+    }
+}
+
+pub struct ByWeightIter<'a, E, F> {
+    iter: EdgeRefs<'a, E>,
+    func: &'a F,
+}
+
+impl<'a, E, F, W> Iterator for ByWeightIter<'a, E, F>
+where
+    F: Fn(&E) -> W,
+{
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        let (v, e) = self.iter.next()?;
+        Some((v, (self.func)(e)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// the same as dijkstra (collected, the view is dropped here)
+pub fn dijkstra_by<N, E, W, F>(
+    graph: &DataGraph<N, E>,
+    start: impl Into<VertexId>,
+    func: F,
+) -> Vec<PathEdge<W>>
+where
+    W: Weight,
+    F: Fn(&E) -> W,
+{
+    dijkstra::dijkstra(&graph.by(func), start).collect()
+}
+
+pub fn bellman_ford_by<N, E, W, F>(
+    graph: &DataGraph<N, E>,
+    start: impl Into<VertexId>,
+    func: F,
+) -> (bool, Vec<Option<W>>, Vec<VertexId>)
+where
+    W: Weight,
+    F: Fn(&E) -> W,
+{
+    bellman_ford::bellman_ford(&graph.by(func), start)
+}
+
+pub fn kruskal_by<N, E, W, F>(graph: &DataGraph<N, E>, func: F) -> Vec<TreeEdge<W>>
+where
+    W: Weight,
+    F: Fn(&E) -> W,
+{
+    kruskal::kruskal(&graph.by(func))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bfs, scc};
+
+    struct Road {
+        name: String,
+        length: u32,
+        lanes: u32,
+    }
+
+    #[test]
+    fn test_data_graph() {
+        let mut g: DataGraph<String, Road> = DataGraph::new();
+        for name in ["a", "b", "c", "d"] {
+            g.add_node(name.to_string());
+        }
+        let road = |name: &str, length, lanes| Road {
+            name: name.to_string(),
+            length,
+            lanes,
+        };
+        g.add_edge(0, 1, road("highway", 10, 4)).unwrap();
+        g.add_edge(0, 1, road("lane", 3, 1)).unwrap(); // parallel edge
+        g.add_edge(1, 2, road("bridge", 5, 2)).unwrap();
+        let id = g.add_edge(0, 2, road("tunnel", 20, 4)).unwrap();
+        assert!(g.add_edge(0, 4, road("nowhere", 1, 1)).is_err());
+
+        // shortest
+        let res = dijkstra_by(&g, 0, |e| e.length);
+        assert_eq!(res, vec![PathEdge::new(0, 1, 3), PathEdge::new(1, 2, 8)]);
+        // fewest lane changes: prefer wide roads
+        let res = dijkstra_by(&g, 0, |e| 4 - e.lanes);
+        assert_eq!(res, vec![PathEdge::new(0, 1, 0), PathEdge::new(0, 2, 0)]);
+        let (state, dist, _from) = bellman_ford_by(&g, 0, |e| e.length as i64);
+        assert!(state);
+        assert_eq!(dist, vec![Some(0), Some(3), Some(8), None]);
+        assert_eq!(kruskal_by(&g, |e| e.length).iter().map(|e| e.weight).sum::<u32>(), 8);
+
+        // unweighted algorithms run on the graph directly
+        assert_eq!(bfs::bfs(&g, 0).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(scc::scc(&g).len(), 4);
+
+        g.get_edge_mut(id).name.push_str(" (closed)");
+        g.get_node_mut(3).push('!');
+        assert_eq!(g.get_edge(id).name, "tunnel (closed)");
+        assert_eq!(g.get_endpoints(id), (0, 2));
+        assert_eq!(g.get_node(3), "d!");
+        assert_eq!(g.edge_count(), 4);
+        let names: Vec<&str> = g.iter_edges_from(0).map(|(_, _, e)| e.name.as_str()).collect();
+        assert_eq!(names, vec!["highway", "lane", "tunnel (closed)"]);
+    }
+}
@@ -1,10 +1,12 @@
-use crate::Graph;
+use crate::{Graph, IndexType, VertexId};
 
-pub fn dfs<G: Graph>(graph: &G, start: usize) -> impl Iterator<Item = usize> + '_ {
+pub fn dfs<G: Graph>(graph: &G, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
+    let start = start.into().index();
     DfsIter::new(graph, start)
 }
 
-pub fn iddfs<G: Graph>(graph: &G, start: usize) -> impl Iterator<Item = usize> + '_ {
+pub fn iddfs<G: Graph>(graph: &G, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
+    let start = start.into().index();
     IddfsIter::new(graph, start)
 }
 
@@ -12,7 +14,7 @@ pub fn iddfs<G: Graph>(graph: &G, start: usize) -> impl Iterator<Item = usize> +
 /// yield vertex as soon as dfs reach it
 struct DfsIter<'a, G: Graph> {
     visited: Vec<bool>,
-    stack: Vec<usize>,
+    stack: Vec<G::Index>,
     graph: &'a G,
 }
 
@@ -20,7 +22,7 @@ impl<'a, G: Graph> DfsIter<'a, G> {
     fn new(graph: &'a G, start: usize) -> Self {
         let mut visited = vec![false; graph.len()];
         visited[start] = true;
-        let stack = vec![start];
+        let stack = vec![G::Index::new(start)];
         Self {
             visited,
             stack,
@@ -34,10 +36,11 @@ impl<'a, G: Graph> Iterator for DfsIter<'a, G> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(u) = self.stack.pop() {
+            let u = u.index();
             for v in self.graph.iter_v_from(u) {
                 if !self.visited[v] {
                     self.visited[v] = true;
-                    self.stack.push(v);
+                    self.stack.push(G::Index::new(v));
                 }
             }
             Some(u)
@@ -54,7 +57,7 @@ impl<'a, G: Graph> Iterator for DfsIter<'a, G> {
 /// (saving a significant amount of memory, with little or no asymptotic slowdown)
 struct IddfsIter<'a, G: Graph> {
     visited: Vec<bool>,
-    stack: Vec<(usize, usize)>,
+    stack: Vec<(G::Index, G::Index)>, // (vertex, depth left), depth < len too
     graph: &'a G,
     start: usize,
     count: usize,
@@ -84,9 +87,10 @@ impl<'a, G: Graph> Iterator for IddfsIter<'a, G> {
         // NOTE: depth's upper bound: the number of vertices
         while self.depth < self.graph.len() {
             if let Some((u, d)) = self.stack.pop() {
+                let (u, d) = (u.index(), d.index());
                 if d > 0 {
                     for v in self.graph.iter_v_from(u) {
-                        self.stack.push((v, d - 1));
+                        self.stack.push((G::Index::new(v), G::Index::new(d - 1)));
                     }
                 }
                 if !self.visited[u] {
@@ -98,7 +102,7 @@ impl<'a, G: Graph> Iterator for IddfsIter<'a, G> {
                 break;
             } else {
                 self.depth += 1;
-                self.stack.push((self.start, self.depth))
+                self.stack.push((G::Index::new(self.start), G::Index::new(self.depth)))
             }
         }
         None
@@ -1,57 +1,212 @@
-use crate::Graph;
-use utils::Heap;
+use crate::heap::IndexedHeap;
+use crate::semiring::{MinPlus, SemiringDijkstraIter};
+use crate::weight::OverflowTracker;
+use crate::{EGraph, Edge, EdgeId, Graph, GraphError, PathEdge, VertexId, Weight};
 
 /// the difference between dijstra and prim's algorithm:
 /// 1. dijstra need to specify a start vertex, while prim needn't
 /// 2. the weight push to the heap: d + w vs w
 /// 3. [NOT Algorithm] dijkstra works on directed graph, while prim on undirected graph
+///
 /// O((E+V)logV) // logV ~ logE
+/// NOTE: overflowed candidates are ignored, so a vertex reached only by overflowed paths
+/// is never yielded (unreachable), use try_dijkstra to get an error instead
 pub fn dijkstra<G: Graph>(
     graph: &G,
-    start: usize,
-) -> impl Iterator<Item = (G::Weight, usize, usize)> + '_ {
-    //DijkstraIter<G>
+    start: impl Into<VertexId>,
+) -> impl Iterator<Item = PathEdge<G::Weight>> + '_ {
+    dijkstra_as(graph, start)
+}
+
+/// distances are accumulated in D rather than G::Weight,
+/// e.g. u8 weights (less memory) with u32 distances (no overflow)
+pub fn dijkstra_as<G, D>(graph: &G, start: impl Into<VertexId>) -> DijkstraIter<'_, G, D>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
     DijkstraIter::new(graph, start)
 }
 
-pub struct DijkstraIter<'a, G: Graph> {
-    graph: &'a G,
-    used: Vec<bool>,
-    heap: Heap<(G::Weight, usize, usize)>,
+/// return Err if `start` is out of range,
+/// the iterator yields Err(Overflow) at last if some distance overflowed (then it ends)
+pub fn try_dijkstra<G: Graph>(graph: &G, start: impl Into<VertexId>) -> Result<TryDijkstraIter<'_, G>, GraphError> {
+    try_dijkstra_as(graph, start)
+}
+
+pub fn try_dijkstra_as<G, D>(
+    graph: &G,
+    start: impl Into<VertexId>,
+) -> Result<TryDijkstraIter<'_, G, D>, GraphError>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    let start = start.into().index();
+    graph.check_vertex(start)?;
+    Ok(TryDijkstraIter {
+        iter: DijkstraIter::new(graph, start),
+        done: false,
+    })
+}
+
+/// D: the type of distance, the same as weight by default
+/// it's SemiringDijkstraIter over MinPlus<D>
+pub struct DijkstraIter<'a, G: Graph, D: Weight = <G as Graph>::Weight> {
+    iter: SemiringDijkstraIter<'a, G, MinPlus<D>, MinPlusEdge<<G as Graph>::Weight, D>>,
+}
+
+type MinPlusEdge<W, D> = fn(usize, usize, W) -> MinPlus<D>;
+
+fn min_plus<W, D: From<W>>(_u: usize, _v: usize, w: W) -> MinPlus<D> {
+    MinPlus(D::from(w))
+}
+
+impl<'a, G, D> DijkstraIter<'a, G, D>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    pub fn new(graph: &'a G, start: impl Into<VertexId>) -> Self {
+        Self::with_arity(graph, start, 2)
+    }
+
+    /// use a d-ary heap, which is faster on dense graph (more decrease_key than pop)
+    pub fn with_arity(graph: &'a G, start: impl Into<VertexId>, arity: usize) -> Self {
+        let edge = min_plus::<G::Weight, D> as MinPlusEdge<G::Weight, D>;
+        Self {
+            iter: SemiringDijkstraIter::with_arity(graph, start, edge, arity),
+        }
+    }
+
+    /// some vertex is reached only by overflowed paths,
+    /// it's only meaningful after all the vertices are yielded
+    pub fn overflowed(&self) -> bool {
+        self.iter.overflowed()
+    }
+}
+
+impl<'a, G, D> Iterator for DijkstraIter<'a, G, D>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    type Item = PathEdge<D>;
+
+    fn next(&mut self) -> Option<PathEdge<D>> {
+        let e = self.iter.next()?;
+        Some(PathEdge::new(e.from, e.to, e.dist.0))
+    }
+}
+
+/// the same as DijkstraIter, but yield Err(Overflow) at last if some distance overflowed
+pub struct TryDijkstraIter<'a, G: Graph, D: Weight = <G as Graph>::Weight> {
+    iter: DijkstraIter<'a, G, D>,
+    done: bool,
 }
 
-impl<'a, G: Graph> DijkstraIter<'a, G> {
-    pub fn new(graph: &'a G, start: usize) -> Self {
-        let mut heap = Heap::new();
-        for (v, w) in graph.iter_e_from(start) {
-            heap.push((w, v, start));
+impl<'a, G, D> Iterator for TryDijkstraIter<'a, G, D>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    type Item = Result<PathEdge<D>, GraphError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.iter.next() {
+            Some(e) => Some(Ok(e)),
+            None => {
+                self.done = true;
+                if self.iter.overflowed() {
+                    Some(Err(GraphError::Overflow))
+                } else {
+                    None
+                }
+            }
         }
-        let mut used = vec![false; graph.len()];
-        used[start] = true;
-        Self { graph, used, heap }
     }
 }
 
-impl<'a, G: Graph> Iterator for DijkstraIter<'a, G> {
-    type Item = (G::Weight, usize, usize);
+/// dijkstra on EGraph (parallel edges are ok), return the shortest path tree:
+/// (distance, the last edge of the shortest path) of every vertex,
+/// None means unreachable (or reached only by overflowed paths), and the edge of `start` is None
+/// NOTE: panic if start is out of range, use try_dijkstra_tree to avoid it
+pub fn dijkstra_tree<W, E>(graph: &EGraph<W, E>, start: impl Into<VertexId>) -> Vec<Option<(W, Option<EdgeId>)>>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    let start = start.into().index();
+    if let Err(e) = graph.check_vertex(start) {
+        panic!("{}", e);
+    }
+    solve_tree(graph, start).0
+}
+
+/// return Err if start is out of range, or some distance overflowed
+#[allow(clippy::type_complexity)]
+pub fn try_dijkstra_tree<W, E>(
+    graph: &EGraph<W, E>,
+    start: impl Into<VertexId>,
+) -> Result<Vec<Option<(W, Option<EdgeId>)>>, GraphError>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    let start = start.into().index();
+    graph.check_vertex(start)?;
+    let (res, tracker) = solve_tree(graph, start);
+    if tracker.overflowed(|v| res[v].is_some()) {
+        Err(GraphError::Overflow)
+    } else {
+        Ok(res)
+    }
+}
 
-    fn next(&mut self) -> Option<(G::Weight, usize, usize)> {
-        while let Some((d, u, v)) = self.heap.pop() {
-            if !self.used[u] {
-                self.used[u] = true;
-                for (v, w) in self.graph.iter_e_from(u) {
-                    self.heap.push((d + w, v, u));
+#[allow(clippy::type_complexity)]
+fn solve_tree<W, E>(graph: &EGraph<W, E>, start: usize) -> (Vec<Option<(W, Option<EdgeId>)>>, OverflowTracker)
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    let n = graph.len();
+    let mut res = vec![None; n];
+    res[start] = Some((W::zero(), None));
+    let mut last = vec![None; n];
+    let mut heap = IndexedHeap::new(n);
+    let mut tracker = OverflowTracker::new(n);
+    let mut u = start;
+    let mut d = W::zero();
+    loop {
+        for (i, v, w) in graph.iter_id_from(u) {
+            if res[v].is_some() {
+                continue;
+            }
+            if let Some(dv) = tracker.add(d, w, v) {
+                if heap.decrease_key(v, (dv, v)) {
+                    last[v] = Some(i);
                 }
-                return Some((d, u, v));
             }
         }
-        None
+        match heap.pop() {
+            Some((v, (dv, _))) => {
+                res[v] = Some((dv, last[v]));
+                (u, d) = (v, dv);
+            }
+            None => break,
+        }
     }
+    (res, tracker)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::MakeGraph;
+    use std::collections::HashMap;
 
     #[test]
     fn test_dijkstra() {
@@ -59,11 +214,57 @@ mod tests {
 
         let u = 7;
         println!("All distances from: {}", s_lst[u]); // h
-        for (w, u, v) in g.dijkstra(u) {
+        for e in g.dijkstra(u) {
             println!(
                 "to: {}, directly from: {}, distance: {}",
-                s_lst[u], s_lst[v], w
+                s_lst[e.to.index()], s_lst[e.from.index()], e.dist
             );
         }
     }
+
+    #[test]
+    fn test_try_dijkstra() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+        assert_eq!(try_dijkstra(&g, 0).unwrap().collect::<Result<Vec<_>, _>>().unwrap().len(), 8);
+        assert!(try_dijkstra(&g, 9).is_err());
+    }
+
+    #[test]
+    fn test_dijkstra_overflow() {
+        let g = crate::VGraph::new(vec![
+            HashMap::from([(1, 200u8), (2, 250)]),
+            HashMap::from([(2, 100)]),
+            HashMap::new(),
+        ]);
+        // 200 + 100 overflowed and ignored, so 0 -> 2 (250) is still the shortest
+        let res: Vec<PathEdge<u8>> = dijkstra(&g, 0).collect();
+        assert_eq!(res, vec![PathEdge::new(0, 1, 200), PathEdge::new(0, 2, 250)]);
+
+        // with u32 distance, no saturation
+        let res: Vec<PathEdge<u32>> = dijkstra_as(&g, 0).collect();
+        assert_eq!(res, vec![PathEdge::new(0, 1, 200), PathEdge::new(0, 2, 250)]);
+        let g = crate::VGraph::new(vec![HashMap::from([(1, 200u8)]), HashMap::from([(2, 60)]), HashMap::new()]);
+        // 200 + 60 overflowed and it's the only path to 2
+        assert_eq!(dijkstra(&g, 0).last(), Some(PathEdge::new(0, 1, 200)));
+        let res: Result<Vec<_>, _> = try_dijkstra(&g, 0).unwrap().collect();
+        assert_eq!(res, Err(GraphError::Overflow));
+        assert_eq!(dijkstra_as::<_, u32>(&g, VertexId(0)).last(), Some(PathEdge::new(1, 2, 260)));
+        let res: Result<Vec<_>, _> = try_dijkstra_as::<_, u32>(&g, 0).unwrap().collect();
+        assert_eq!(res.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dijkstra_tree() {
+        let mut g = EGraph::new(vec![], 3);
+        g.add_edge((0, 1, 5)).unwrap();
+        let a = g.add_edge((0, 1, 2)).unwrap();
+        let b = g.add_edge((1, 2, 1)).unwrap();
+        let res = dijkstra_tree(&g, 0);
+        assert_eq!(res, vec![Some((0, None)), Some((2, Some(a))), Some((3, Some(b)))]);
+        assert_eq!(try_dijkstra_tree(&g, 0), Ok(res));
+        assert_eq!(try_dijkstra_tree(&g, 3), Err(GraphError::VertexOutOfRange { index: 3, len: 3 }));
+        let g = EGraph::new(vec![(0, 1, 200u8), (1, 2, 100)], 3);
+        assert_eq!(dijkstra_tree(&g, 0)[2], None);
+        assert_eq!(try_dijkstra_tree(&g, 0), Err(GraphError::Overflow));
+    }
 }
@@ -1,9 +1,16 @@
-/// iter_e_around()
-
-use crate::{EGraph, FlowEdge, Graph, Weight};
+/// iter_e_around(), the flow is kept in the edges of EGraph
+use crate::{EGraph, FlowEdge, Graph, VertexId, Weight};
 use std::collections::VecDeque;
 
-pub fn dinic<W: Weight>(graph: &mut EGraph<W, FlowEdge<W>>, start: usize, target: usize) -> W {
+/// return the max flow, the flow of every edge is written back to the graph
+/// (see EGraph::get_flow), call EGraph::reset_flow before running it again
+pub fn dinic<W: Weight>(
+    graph: &mut EGraph<W, FlowEdge<W>>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> W {
+    let start = start.into().index();
+    let target = target.into().index();
     let mut obj = DinicDfs::new(graph.len(), start, target);
     let mut flow = Default::default();
     loop {
@@ -18,7 +25,6 @@ pub fn dinic<W: Weight>(graph: &mut EGraph<W, FlowEdge<W>>, start: usize, target
 }
 
 struct DinicDfs {
-    //graph: &'a mut EGraph<W, FlowEdge<W>>, //edges: Vec<Edge<W>>,
     dist: Vec<i32>,
     start: usize,
     target: usize,
@@ -26,17 +32,13 @@ struct DinicDfs {
 
 impl DinicDfs {
     fn new(n: usize, start: usize, target: usize) -> Self {
-        //graph: &'a mut EGraph<W, FlowEdge<W>>,
         let dist = vec![-1; n];
         Self {
-            //graph,
             dist,
             start,
             target,
         }
-        // since we should recalc dist every loop, no need to calc here
-        // obj.calc_dist();
-        // obj
+        // NOTE: since we should recalc dist every loop, no need to calc here
     }
 
     /// use bfs to calc dist
@@ -49,7 +51,8 @@ impl DinicDfs {
         queue.push_back(self.start);
         let zero: W = Default::default();
         while let Some(u) = queue.pop_front() {
-            for e in graph.iter_e_around(u) {
+            for i in graph.iter_e_around(u) {
+                let e = graph.get_edge(i);
                 if e.from == u {
                     let v = e.to;
                     if self.dist[v] == -1 && e.cap > e.flow {
@@ -67,25 +70,17 @@ impl DinicDfs {
         }
     }
 
-    /// since no inf can be used, we put the first loop apart
-    /// and it's not bad, since as to first loop, no backward will happen
+    /// start from infinity, dinic_dfs will cut it down to the capacities
     fn dinic_augment<W: Weight>(&self, graph: &mut EGraph<W, FlowEdge<W>>) -> W {
-        let mut flow = Default::default();
-
-        for i in graph.v_lst[self.start].clone() {
-            let e = &graph.e_lst[i];
-            if self.dist[e.to] == self.dist[self.start] + 1 {
-                let nxt = e.to;
-                let thres = e.cap - e.flow;
-                let f_bak = self.dinic_dfs(nxt, thres, graph);
-                graph.e_lst[i].flow += f_bak;
-                flow += f_bak;
-            }
+        if self.start == self.target {
+            return Default::default();
         }
-        flow
+        self.dinic_dfs(self.start, W::infinity(), graph)
     }
 
     /// use dfs to find mutli augmenting paths
+    /// NOTE: we use index rather than iterator to loop edges,
+    /// since the graph need to be mutated in the loop
     fn dinic_dfs<W: Weight>(
         &self,
         cur: usize,
@@ -97,23 +92,28 @@ impl DinicDfs {
         }
         let mut flow: W = Default::default();
 
-        for i in graph.v_lst[cur].clone() {
+        for j in 0..graph.from_v_lst[cur].len() {
+            let i = graph.from_v_lst[cur][j];
             let e = &graph.e_lst[i];
-            if e.from == cur {
-                if self.dist[e.to] == self.dist[cur] + 1 {
-                    let nxt = e.to;
-                    let thres = min(e.cap - e.flow, f_max);
-                    let f_bak = self.dinic_dfs(nxt, thres, graph);
-                    graph.e_lst[i].flow += f_bak;
-                    flow += f_bak;
-                    f_max -= f_bak;
-                    if f_max.is_zero() {
-                        break;
-                    }
+            if self.dist[e.to] == self.dist[cur] + 1 {
+                let nxt = e.to;
+                let thres = f_max.min(e.cap - e.flow);
+                let f_bak = self.dinic_dfs(nxt, thres, graph);
+                graph.e_lst[i].flow += f_bak;
+                flow += f_bak;
+                f_max -= f_bak;
+                if f_max.is_zero() {
+                    return flow;
                 }
-            } else if self.dist[e.from] == self.dist[cur] + 1 {
+            }
+        }
+
+        for j in 0..graph.to_v_lst[cur].len() {
+            let i = graph.to_v_lst[cur][j];
+            let e = &graph.e_lst[i];
+            if self.dist[e.from] == self.dist[cur] + 1 {
                 let nxt = e.from;
-                let thres = min(e.flow, f_max);
+                let thres = f_max.min(e.flow);
                 let f_bak = self.dinic_dfs(nxt, thres, graph);
                 graph.e_lst[i].flow -= f_bak;
                 flow += f_bak;
@@ -127,14 +127,6 @@ impl DinicDfs {
     }
 }
 
-fn min<W: Weight>(x: W, y: W) -> W {
-    if x > y {
-        y
-    } else {
-        x
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,8 +134,34 @@ mod tests {
 
     #[test]
     fn test_dinic() {
-        let (mut g, s_lst) = MakeGraph::mf();
+        let (mut g, s_lst) = MakeGraph::mf_egraph();
         let (s, t) = (0, 5);
-        dbg!(dinic(&mut g, s, t));
+        assert_eq!(dinic(&mut g, s, t), 23);
+
+        for (_, e) in g.iter_edges() {
+            println!(
+                "{} -> {}: {}/{}",
+                s_lst[e.from],
+                s_lst[e.to],
+                e.get_flow(),
+                e.cap
+            );
+        }
+
+        // run again after reset, we should get the same answer
+        g.reset_flow();
+        assert_eq!(g.dinic(s, t), 23);
+    }
+
+    #[test]
+    fn test_dinic_parallel_edges() {
+        let mut g = EGraph::new(vec![], 3);
+        let a = g.add_edge(FlowEdge::new(0, 1, 3)).unwrap();
+        let b = g.add_edge(FlowEdge::new(0, 1, 4)).unwrap();
+        let c = g.add_edge(FlowEdge::new(1, 2, 5)).unwrap();
+        assert_eq!(dinic(&mut g, 0, 2), 5);
+        assert_eq!(g.get_flow(a) + g.get_flow(b), 5);
+        assert_eq!(g.get_flow(c), 5);
+        assert_eq!(g.iter_flow().map(|(_, f)| f).sum::<i32>(), 10);
     }
 }
@@ -1,12 +1,16 @@
 /// iter_e_from(), iter_v_to()
-use crate::{Graph, Weight};
+use crate::edmonds_karp::capacities;
+use crate::{Graph, VertexId, Weight};
 use std::collections::{HashMap, VecDeque};
 
+/// flowing[v][u] is the flow on u -> v, parallel edges u -> v share it (their capacities are summed up)
 pub fn dinic<G: Graph>(
     graph: &G,
-    start: usize,
-    target: usize,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
 ) -> HashMap<usize, HashMap<usize, G::Weight>> {
+    let start = start.into().index();
+    let target = target.into().index();
     let mut obj = DinicDfs::new(graph, start, target);
     //let mut flow = Default::default();
     let mut flowing = HashMap::new();
@@ -24,6 +28,7 @@ pub fn dinic<G: Graph>(
 
 struct DinicDfs<'a, G: Graph> {
     graph: &'a G,
+    caps: HashMap<(usize, usize), G::Weight>, // parallel edges are summed up
     dist: Vec<i32>,
     start: usize,
     target: usize,
@@ -34,6 +39,7 @@ impl<'a, G: Graph> DinicDfs<'a, G> {
         let dist = vec![-1; graph.len()];
         Self {
             graph,
+            caps: capacities(graph),
             dist,
             start,
             target,
@@ -51,8 +57,8 @@ impl<'a, G: Graph> DinicDfs<'a, G> {
         queue.push_back(self.start);
         let zero = Default::default();
         while let Some(u) = queue.pop_front() {
-            for (v, w) in self.graph.iter_e_from(u) {
-                if self.dist[v] == -1 && w > get_flow_in(flowing, v, u) {
+            for v in self.graph.iter_v_from(u) {
+                if self.dist[v] == -1 && self.caps[&(u, v)] > get_flow_in(flowing, v, u) {
                     queue.push_back(v);
                     self.dist[v] = self.dist[u] + 1;
                 }
@@ -66,20 +72,12 @@ impl<'a, G: Graph> DinicDfs<'a, G> {
         }
     }
 
-    /// since no inf can be used, we put the first loop apart
-    /// and it's not bad, since as to first loop, no backward will happen
+    /// start from infinity, dinic_dfs will cut it down to the capacities
     fn dinic_augment(&self, flowing: &mut HashMap<usize, HashMap<usize, G::Weight>>) -> G::Weight {
-        let mut flow = Default::default();
-
-        for (v, w) in self.graph.iter_e_from(self.start) {
-            if self.dist[v] == self.dist[self.start] + 1 {
-                let thres = w - get_flow_in(flowing, v, self.start);
-                let f_bak = self.dinic_dfs(v, thres, flowing);
-                add_flow_in(flowing, v, self.start, f_bak);
-                flow += f_bak;
-            }
+        if self.start == self.target {
+            return Default::default();
         }
-        flow
+        self.dinic_dfs(self.start, G::Weight::infinity(), flowing)
     }
 
     /// use dfs to find mutli augmenting paths
@@ -94,9 +92,9 @@ impl<'a, G: Graph> DinicDfs<'a, G> {
         }
         let mut flow = Default::default();
 
-        for (v, w) in self.graph.iter_e_from(cur) {
+        for v in self.graph.iter_v_from(cur) {
             if self.dist[v] == self.dist[cur] + 1 {
-                let thres = f_max.min(w - get_flow_in(flowing, v, cur));
+                let thres = f_max.min(self.caps[&(cur, v)] - get_flow_in(flowing, v, cur));
                 let f_bak = self.dinic_dfs(v, thres, flowing);
                 add_flow_in(flowing, v, cur, f_bak);
                 f_max -= f_bak;
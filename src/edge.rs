@@ -9,6 +9,11 @@ pub trait Edge<W: Weight>: Copy + std::fmt::Debug {
 
 }
 
+/// stable identifier of an edge in EGraph (it is the edge's index in the edge list)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeId(pub usize);
+
 /// most common edge: (from, to, weight) tuple
 // type WeightedEdge<W: Weight> = (usize, usize, W); // no bound needed
 impl<W: Weight> Edge<W> for (usize, usize, W) {
@@ -31,6 +36,7 @@ impl<W: Weight> Edge<W> for (usize, usize, W) {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FlowEdge<W: Weight> {
     pub(crate) from: usize,
     pub(crate) to: usize,
@@ -48,6 +54,10 @@ impl<W: Weight> FlowEdge<W> {
             flow,
         }
     }
+
+    pub fn get_flow(&self) -> W {
+        self.flow
+    }
 }
 
 impl<W: Weight> Edge<W> for FlowEdge<W> {
@@ -72,6 +82,7 @@ impl<W: Weight> Edge<W> for FlowEdge<W> {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CostFlowEdge<C: Weight, W: Weight> {
     pub(crate) from: usize,
     pub(crate) to: usize,
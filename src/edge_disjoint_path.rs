@@ -1,13 +1,15 @@
-use crate::Graph;
+use crate::{Graph, VertexId};
 use std::collections::{HashMap, HashSet};
 
 /// you can get the count of edge disjoint path by: matching[t].len()
 /// you also can get one paths solution: see #[test]
 pub fn edge_disjoint_path<G: Graph>(
     graph: &G,
-    start: usize,
-    target: usize,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
 ) -> HashMap<usize, HashSet<usize>> {
+    let start = start.into().index();
+    let target = target.into().index();
     let mut matching = HashMap::new();
     while edge_disjoint_augment(graph, &mut matching, start, target) {}
     matching
@@ -45,7 +47,10 @@ fn edge_disjoint_augment<G: Graph>(
             break;
         }
         if let Some(out) = matching.get(&u) {
-            for &v in out.iter() {
+            // sorted, so the result only depends on the order of graph's edges
+            let mut out: Vec<usize> = out.iter().copied().collect();
+            out.sort();
+            for v in out {
                 path.entry(v).or_insert_with(|| {
                     stack.push(v);
                     u
@@ -60,7 +65,7 @@ fn edge_disjoint_augment<G: Graph>(
         while v != start {
             let u = *path.get(&v).unwrap();
 
-            let out = matching.entry(v).or_insert_with(HashSet::new);
+            let out = matching.entry(v).or_default();
             if out.contains(&u) {
                 out.remove(&u);
             } else {
@@ -1,4 +1,4 @@
-use crate::{Graph, Weight};
+use crate::{EGraph, EdgeId, FlowEdge, Graph, VertexId, Weight};
 use std::collections::{HashMap, VecDeque};
 
 /// O(VE^2) find an augmenting path cost E
@@ -8,18 +8,34 @@ use std::collections::{HashMap, VecDeque};
 /// https://www.zhihu.com/question/38281136/answer/88295342
 /// NOTE1: 对角线是（上下，左右）交替出现
 /// NOTE2: 所有边的方向都是 左->右 上->下
+/// NOTE3: flows are keyed by (u, v), parallel edges share one (their capacities are summed up),
+/// use edmonds_karp_egraph for the flow of every edge
 pub fn edmonds_karp<G: Graph>(
     graph: &G,
-    start: usize,
-    target: usize,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
 ) -> HashMap<usize, HashMap<usize, G::Weight>> {
+    let start = start.into().index();
+    let target = target.into().index();
+    let caps = capacities(graph);
     let mut flowing = HashMap::new();
-    while edmonds_karp_augment(graph, &mut flowing, start, target) {}
+    while edmonds_karp_augment(graph, &caps, &mut flowing, start, target) {}
     flowing
 }
 
+/// the capacity of every u -> v, parallel edges are summed up,
+/// since the flow of them is kept in one entry: flowing[v][u]
+pub(crate) fn capacities<G: Graph>(graph: &G) -> HashMap<(usize, usize), G::Weight> {
+    let mut caps = HashMap::new();
+    for (u, v, w) in graph.iter_e_all() {
+        *caps.entry((u, v)).or_insert_with(G::Weight::zero) += w;
+    }
+    caps
+}
+
 fn edmonds_karp_augment<G: Graph>(
     graph: &G,
+    caps: &HashMap<(usize, usize), G::Weight>,
     flowing: &mut HashMap<usize, HashMap<usize, G::Weight>>,
     start: usize,
     target: usize,
@@ -28,21 +44,30 @@ fn edmonds_karp_augment<G: Graph>(
     let zero = Default::default();
     let mut queue = VecDeque::new(); // because we can not use inf
     let mut path: HashMap<usize, usize> = HashMap::new();
-    for (v, w) in graph.iter_e_from(start) {
-        let left = w - get_weight_in(flowing, v, start);
+    let mut w_add = zero;
+    for v in graph.iter_v_from(start) {
+        if path.contains_key(&v) {
+            continue; // a parallel edge
+        }
+        let left = caps[&(start, v)] - get_weight_in(flowing, v, start);
         if left != zero {
             queue.push_back((v, left));
             path.insert(v, start);
+            if v == target {
+                w_add = left;
+            }
         }
     }
-    let mut w_add = zero;
 
     while let Some((u, w_max)) = queue.pop_front() {
-        for (v, w) in graph.iter_e_from(u) {
+        if path.contains_key(&target) {
+            break;
+        }
+        for v in graph.iter_v_from(u) {
             if let std::collections::hash_map::Entry::Vacant(e) = path.entry(v) {
                 // clippy taught me this
                 //if !path.contains_key(&v) {
-                let left = w - get_weight_in(flowing, v, u);
+                let left = caps[&(u, v)] - get_weight_in(flowing, v, u);
                 if left != zero {
                     queue.push_back((v, w_max.min(left)));
                     //path.insert(v, u);
@@ -58,7 +83,10 @@ fn edmonds_karp_augment<G: Graph>(
             break;
         }
         if let Some(out) = flowing.get(&u) {
-            for (&v, &w) in out.iter() {
+            // sorted, so the result only depends on the order of graph's edges
+            let mut out: Vec<(usize, G::Weight)> = out.iter().map(|(&v, &w)| (v, w)).collect();
+            out.sort_by_key(|&(v, _)| v);
+            for (v, w) in out {
                 path.entry(v).or_insert_with(|| {
                     queue.push_back((v, w_max.min(w)));
                     u
@@ -103,8 +131,77 @@ fn get_weight_in<W: Weight>(flowing: &HashMap<usize, HashMap<usize, W>>, u: usiz
     Default::default()
 }
 
+/// the same algorithm on EGraph (parallel edges are ok): the residual graph is walked by EdgeId,
+/// the flow of every edge is written back to the graph (see EGraph::get_flow), return the max flow
+pub fn edmonds_karp_egraph<W: Weight>(
+    graph: &mut EGraph<W, FlowEdge<W>>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> W {
+    let start = start.into().index();
+    let target = target.into().index();
+    let mut flow = W::zero();
+    if start == target {
+        return flow;
+    }
+    while let Some(path) = find_augmenting_path(graph, start, target) {
+        let mut w_add = W::infinity();
+        for &(id, forward) in path.iter() {
+            let e = graph.get_edge(id);
+            w_add = w_add.min(if forward { e.cap - e.flow } else { e.flow });
+        }
+        for &(id, forward) in path.iter() {
+            if forward {
+                *graph.get_flow_mut(id) += w_add;
+            } else {
+                *graph.get_flow_mut(id) -= w_add;
+            }
+        }
+        flow += w_add;
+    }
+    flow
+}
+
+/// bfs on the residual graph, return the edges of the path: (id, forward)
+fn find_augmenting_path<W: Weight>(
+    graph: &EGraph<W, FlowEdge<W>>,
+    start: usize,
+    target: usize,
+) -> Option<Vec<(EdgeId, bool)>> {
+    let zero = W::zero();
+    let mut from: Vec<Option<(EdgeId, bool)>> = vec![None; graph.len()];
+    let mut queue = VecDeque::from([start]);
+    while let Some(u) = queue.pop_front() {
+        for id in graph.iter_e_around(u) {
+            let e = graph.get_edge(id);
+            let (v, left, forward) = if e.from == u {
+                (e.to, e.cap - e.flow, true)
+            } else {
+                (e.from, e.flow, false)
+            };
+            if v != start && from[v].is_none() && left > zero {
+                from[v] = Some((id, forward));
+                if v == target {
+                    let mut path = vec![];
+                    let mut v = target;
+                    while v != start {
+                        let (id, forward) = from[v].unwrap(); // it's ok to unwrap
+                        path.push((id, forward));
+                        let e = graph.get_edge(id);
+                        v = if forward { e.from } else { e.to };
+                    }
+                    return Some(path);
+                }
+                queue.push_back(v);
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::MakeGraph;
 
     #[test]
@@ -124,4 +221,19 @@ mod tests {
             println!("}}");
         }
     }
+
+    #[test]
+    fn test_edmonds_karp_parallel_edges() {
+        let edges = vec![(0, 1, 3), (0, 1, 4), (1, 2, 100)];
+        let mut g = EGraph::new(edges.iter().map(|&(u, v, w)| FlowEdge::new(u, v, w)).collect(), 3);
+        assert_eq!(g.edmonds_karp(0, 2), 7);
+        assert_eq!(g.get_flow(EdgeId(0)), 3);
+        assert_eq!(g.get_flow(EdgeId(1)), 4);
+        assert_eq!(g.get_flow(EdgeId(2)), 7);
+
+        // (u, v) keyed algorithms sum parallel edges up
+        let g = crate::MultiGraph::new(edges, 3);
+        assert_eq!(edmonds_karp(&g, 0, 2)[&2][&1], 7);
+        assert_eq!(crate::dinic_new::dinic(&g, 0, 2)[&2][&1], 7);
+    }
 }
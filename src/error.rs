@@ -0,0 +1,66 @@
+/// the error type of this crate
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// vertex `index` is not in a graph of `len` vertices
+    VertexOutOfRange { index: usize, len: usize },
+    /// there is no edge `from` -> `to`
+    EdgeNotFound { from: usize, to: usize },
+    /// a vertex of the same label is already in the graph
+    DuplicateLabel(String),
+    /// no vertex of the label in the graph
+    UnknownLabel(String),
+    /// a negative cycle is reachable, so some shortest paths do not exist
+    NegativeCycle,
+    /// edge `from` -> `to` is given more than once (GraphBuilder)
+    DuplicateEdge { from: usize, to: usize },
+    /// an edge from vertex `u` to itself is not allowed (GraphBuilder)
+    SelfLoop(usize),
+    /// edge `from` -> `to` has no capacity given (mcmf)
+    MissingCapacity { from: usize, to: usize },
+    /// the algorithm needs at least one vertex
+    EmptyGraph,
+    /// the sum of weights overflowed
+    Overflow,
+    /// the value can not be used as a weight (e.g. NaN)
+    InvalidWeight(String),
+    /// a graph of `len` vertices can not be indexed by the index type (at most `max` vertices)
+    IndexOverflow { len: usize, max: usize },
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::VertexOutOfRange { index, len } => {
+                write!(f, "vertex {} out of range (graph has {} vertices)", index, len)
+            }
+            Self::EdgeNotFound { from, to } => write!(f, "edge {} -> {} not found", from, to),
+            Self::DuplicateLabel(label) => write!(f, "duplicated label: {}", label),
+            Self::UnknownLabel(label) => write!(f, "unknown label: {}", label),
+            Self::NegativeCycle => write!(f, "negative cycle found"),
+            Self::DuplicateEdge { from, to } => write!(f, "duplicated edge {} -> {}", from, to),
+            Self::SelfLoop(u) => write!(f, "self-loop on vertex {}", u),
+            Self::MissingCapacity { from, to } => {
+                write!(f, "capacity of edge {} -> {} not found", from, to)
+            }
+            Self::EmptyGraph => write!(f, "empty graph"),
+            Self::Overflow => write!(f, "weight overflowed"),
+            Self::InvalidWeight(value) => write!(f, "invalid weight: {}", value),
+            Self::IndexOverflow { len, max } => {
+                write!(f, "{} vertices exceed the index type (at most {})", len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// helper to check vertex `u` is in a graph of `len` vertices
+pub(crate) fn check_vertex(u: usize, len: usize) -> Result<(), GraphError> {
+    if u < len {
+        Ok(())
+    } else {
+        Err(GraphError::VertexOutOfRange { index: u, len })
+    }
+}
@@ -1,46 +1,86 @@
-use crate::Graph;
+use crate::semiring::{solve_floyd_warshall, MinPlus};
+use crate::view::MapWeight;
+use crate::{Graph, GraphError, MatrixGraph, Weight};
 
 /// can not process negative cycle
 /// O(V^3)
 /// if W is float, we can use const INFINITY to speed up?
+/// NOTE: overflowed candidates are ignored (see bellman_ford), use try_floyd_warshall to get an error instead
 pub fn floyd_warshall<G: Graph>(graph: &G) -> Vec<Vec<Option<G::Weight>>> {
-    let n = graph.len();
-    let mut dist = vec![vec![None; n]; n];
-    for (u, dist_u) in dist.iter_mut().enumerate() {
-        dist_u[u] = Some(Default::default());
-        for (v, w) in graph.iter_e_from(u) {
-            dist_u[v] = Some(w);
-        }
+    floyd_warshall_dist(graph).0
+}
+
+/// distances are accumulated in D rather than G::Weight (see dijkstra_as)
+pub fn floyd_warshall_as<G, D>(graph: &G) -> Vec<Vec<Option<D>>>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    floyd_warshall(&MapWeight::new(graph, |_, _, w| D::from(w)))
+}
+
+#[allow(clippy::type_complexity)]
+pub fn try_floyd_warshall_as<G, D>(graph: &G) -> Result<Vec<Vec<Option<D>>>, GraphError>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    try_floyd_warshall(&MapWeight::new(graph, |_, _, w| D::from(w)))
+}
+
+/// return Err if negative cycle existed (some dist[u][u] < 0) or some distance overflowed
+#[allow(clippy::type_complexity)]
+pub fn try_floyd_warshall<G: Graph>(graph: &G) -> Result<Vec<Vec<Option<G::Weight>>>, GraphError> {
+    check_overflow(floyd_warshall_dist(graph))
+}
+
+/// check negative cycle first, since it may make distances overflow
+#[allow(clippy::type_complexity)]
+pub(crate) fn check_overflow<W: Weight>(
+    (dist, overflowed): (Vec<Vec<Option<W>>>, bool),
+) -> Result<Vec<Vec<Option<W>>>, GraphError> {
+    let dist = check_negative_cycle(dist)?;
+    if overflowed {
+        Err(GraphError::Overflow)
+    } else {
+        Ok(dist)
     }
+}
 
-    for i in 0..n {
-        for u in 0..n {
-            if u == i {
-                // we also can write conditions: v != i, v != u
-                // but I'm not sure how them will influnce the speed
-                continue;
-            }
-            for v in 0..n {
-                if let Some(d1) = dist[u][i] {
-                    if let Some(d2) = dist[i][v] {
-                        if let Some(d0) = dist[u][v] {
-                            if d1 + d2 < d0 {
-                                dist[u][v] = Some(d1 + d2);
-                            }
-                        } else {
-                            dist[u][v] = Some(d1 + d2);
-                        }
-                    }
-                }
+/// some dist[u][u] < 0 means a negative cycle
+#[allow(clippy::type_complexity)]
+pub(crate) fn check_negative_cycle<W: Weight>(dist: Vec<Vec<Option<W>>>) -> Result<Vec<Vec<Option<W>>>, GraphError> {
+    for (u, dist_u) in dist.iter().enumerate() {
+        if let Some(d) = dist_u[u] {
+            if d.is_negative() {
+                return Err(GraphError::NegativeCycle);
             }
         }
     }
-    dist
+    Ok(dist)
+}
+
+/// the same as floyd_warshall
+pub fn floyd_warshall_matrix<W: Weight>(graph: &MatrixGraph<W>) -> Vec<Vec<Option<W>>> {
+    floyd_warshall(graph)
+}
+
+/// return (dist, overflowed?), it's semiring_floyd_warshall over MinPlus
+#[allow(clippy::type_complexity)]
+fn floyd_warshall_dist<G: Graph>(graph: &G) -> (Vec<Vec<Option<G::Weight>>>, bool) {
+    let (value, overflowed) = solve_floyd_warshall(graph, |_, _, w| MinPlus(w));
+    let dist = value
+        .into_iter()
+        .map(|value_u| value_u.into_iter().map(|x| x.map(|x| x.0)).collect())
+        .collect();
+    (dist, overflowed)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, VGraph};
+    use std::collections::HashMap;
 
     #[test]
     fn test_floyd_warshall() {
@@ -57,4 +97,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_try_floyd_warshall() {
+        let (g, _s_lst) = MakeGraph::spn();
+        assert_eq!(try_floyd_warshall(&g).unwrap(), g.floyd_warshall());
+        let g = VGraph::new(vec![HashMap::from([(1, 1)]), HashMap::from([(0, -2)])]);
+        assert_eq!(try_floyd_warshall(&g), Err(GraphError::NegativeCycle));
+
+        let g = VGraph::new(vec![HashMap::from([(1, 200u8)]), HashMap::from([(2, 100)]), HashMap::new()]);
+        assert_eq!(try_floyd_warshall(&g), Err(GraphError::Overflow));
+        assert_eq!(g.floyd_warshall()[0][2], None);
+        assert_eq!(try_floyd_warshall_as::<_, u32>(&g).unwrap()[0][2], Some(300));
+        assert_eq!(floyd_warshall_as::<_, u16>(&g)[0][2], Some(300));
+
+        // 0 -> 1 -> 3 overflowed, but it's not the shortest path 0 -> 2 -> 1 -> 3
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 200u8), (2, 10)]),
+            HashMap::from([(3, 100)]),
+            HashMap::from([(1, 10)]),
+            HashMap::new(),
+        ]);
+        assert_eq!(try_floyd_warshall(&g).unwrap()[0], vec![Some(0), Some(20), Some(10), Some(120)]);
+    }
 }
@@ -1,16 +1,37 @@
 /// One trait: Graph
 /// Two kinds of struct which implemnted Graph trait: VGraph, EGraph
 /// serveral macros:
-//use crate::{Edge, Weight, WeightedEdge, CostFlowEdge};
-use crate::Weight;
-use std::collections::HashMap;
-// use std::marker::PhantomData;
+use crate::error::check_vertex;
+use crate::index::check_len;
+use crate::{CostFlowEdge, Edge, EdgeId, FlowEdge, GraphError, IndexType, VertexId, Weight};
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+use std::marker::PhantomData;
 
 /// all `u` in this trait's method may >= self.len(), this may panic
-/// we do not handle this error for simplicity
+/// we do not handle this error for simplicity (use check_vertex or try_ functions)
+/// every `u`/`v` is impl Into<VertexId>, so both VertexId and usize are accepted
 pub trait Graph {
     type Weight: Weight;
 
+    /// the type vertex indices are stored in, algorithms keep their per-vertex state
+    /// (from, queue, stack) in it too, usize for most graphs
+    type Index: IndexType;
+
+    /// the iterators are concrete types rather than Box<dyn Iterator>,
+    /// so expanding a vertex neither allocates nor loses inlining
+    type IterVFrom<'a>: Iterator<Item = usize>
+    where
+        Self: 'a;
+    type IterVTo<'a>: Iterator<Item = usize>
+    where
+        Self: 'a;
+    type IterEFrom<'a>: Iterator<Item = (usize, Self::Weight)>
+    where
+        Self: 'a;
+    type IterETo<'a>: Iterator<Item = (usize, Self::Weight)>
+    where
+        Self: 'a;
+
     /// return the number of vertices
     fn len(&self) -> usize;
 
@@ -18,21 +39,35 @@ pub trait Graph {
         self.len() == 0
     }
 
+    /// Ok if vertex `u` is in the graph, used by try_ functions to avoid panic
+    fn check_vertex(&self, u: impl Into<VertexId>) -> Result<(), GraphError> {
+        let u = u.into().index();
+        check_vertex(u, self.len())
+    }
+
     /// iter all the vertices from vertex `u`
-    fn iter_v_from(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_>;
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_>;
 
-    fn is_empty_from(&self, u: usize) -> bool {
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
         self.iter_v_from(u).next().is_none()
     }
 
-    fn iter_v_to(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_>;
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_>;
 
-    fn is_empty_to(&self, u: usize) -> bool {
+    /// true if iter_*_to is O(degree) like iter_*_from, rather than a scan of every vertex,
+    /// algorithms walking in-edges (e.g. scc) build a reversed copy when it is false
+    fn has_fast_iter_to(&self) -> bool {
+        false
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
         self.iter_v_to(u).next().is_none()
     }
 
     /// iter all the edges from vertex `u`
-    fn iter_e_from(&self, u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_>;
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_>;
 
     // fn iter_mut_e_from(
     //     &mut self,
@@ -40,63 +75,477 @@ pub trait Graph {
     // ) -> Box<dyn Iterator<Item = (usize, &mut Self::Weight)> + '_>;
 
     /// iter all the edges to vertex `u`
-    fn iter_e_to(&self, u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_>;
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_>;
 
     // fn iter_mut_e_to(
     //     &mut self,
     //     u: usize,
     // ) -> Box<dyn Iterator<Item = (usize, &mut Self::Weight)> + '_>;
+
+    /// the number of edges (u -> v and v -> u are two edges)
+    fn edge_count(&self) -> usize {
+        (0..self.len()).map(|u| self.out_degree(u)).sum()
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.iter_v_from(u).count()
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.iter_v_to(u).count()
+    }
+
+    /// panics if u is out of range, VGraph and MatrixGraph have try_has_edge/try_weight
+    fn has_edge(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.iter_v_from(u).any(|x| x == v)
+    }
+
+    /// the weight of edge u -> v, None if no such edge
+    fn weight(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> Option<Self::Weight> {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.iter_e_from(u).find(|&(x, _)| x == v).map(|(_, w)| w)
+    }
+
+    /// iter all the edges (u, v, w) of the graph, ordered by u
+    fn iter_e_all(&self) -> AllEdges<'_, Self>
+    where
+        Self: Sized,
+    {
+        AllEdges {
+            graph: self,
+            u: 0,
+            iter: None,
+        }
+    }
+}
+
+/// all the edges (u, v, w) of a graph, see Graph::iter_e_all
+pub struct AllEdges<'a, G: Graph> {
+    graph: &'a G,
+    u: usize,
+    iter: Option<G::IterEFrom<'a>>,
+}
+
+impl<'a, G: Graph> Iterator for AllEdges<'a, G> {
+    type Item = (usize, usize, G::Weight);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.u < self.graph.len() {
+            let iter = self.iter.get_or_insert_with(|| self.graph.iter_e_from(self.u));
+            if let Some((v, w)) = iter.next() {
+                return Some((self.u, v, w));
+            }
+            self.iter = None;
+            self.u += 1;
+        }
+        None
+    }
+}
+
+/// turn an edge iterator (v, w) into a vertex iterator v
+pub struct Vertices<I>(pub(crate) I);
+
+impl<I, W> Iterator for Vertices<I>
+where
+    I: Iterator<Item = (usize, W)>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.0.next().map(|(v, _)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// the map v -> w of a vertex in VGraph:
+/// HashMap (the fastest) or BTreeMap (iterated in the order of v, so results are reproducible)
+pub trait Adjacency<Ix, W>: Default + Clone + IntoIterator<Item = (Ix, W)> + FromIterator<(Ix, W)> {
+    type Iter<'a>: Iterator<Item = (&'a Ix, &'a W)>
+    where
+        Self: 'a,
+        Ix: 'a,
+        W: 'a;
+
+    fn insert(&mut self, v: Ix, w: W) -> Option<W>;
+
+    fn remove(&mut self, v: &Ix) -> Option<W>;
+
+    fn get(&self, v: &Ix) -> Option<&W>;
+
+    fn get_mut(&mut self, v: &Ix) -> Option<&mut W>;
+
+    fn is_empty(&self) -> bool;
+
+    fn len(&self) -> usize;
+
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<Ix: IndexType, W: Copy> Adjacency<Ix, W> for HashMap<Ix, W> {
+    type Iter<'a> = hash_map::Iter<'a, Ix, W> where Self: 'a, Ix: 'a, W: 'a;
+
+    fn insert(&mut self, v: Ix, w: W) -> Option<W> {
+        HashMap::insert(self, v, w)
+    }
+
+    fn remove(&mut self, v: &Ix) -> Option<W> {
+        HashMap::remove(self, v)
+    }
+
+    fn get(&self, v: &Ix) -> Option<&W> {
+        HashMap::get(self, v)
+    }
+
+    fn get_mut(&mut self, v: &Ix) -> Option<&mut W> {
+        HashMap::get_mut(self, v)
+    }
+
+    fn is_empty(&self) -> bool {
+        HashMap::is_empty(self)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashMap::iter(self)
+    }
+}
+
+impl<Ix: IndexType, W: Copy> Adjacency<Ix, W> for BTreeMap<Ix, W> {
+    type Iter<'a> = btree_map::Iter<'a, Ix, W> where Self: 'a, Ix: 'a, W: 'a;
+
+    fn insert(&mut self, v: Ix, w: W) -> Option<W> {
+        BTreeMap::insert(self, v, w)
+    }
+
+    fn remove(&mut self, v: &Ix) -> Option<W> {
+        BTreeMap::remove(self, v)
+    }
+
+    fn get(&self, v: &Ix) -> Option<&W> {
+        BTreeMap::get(self, v)
+    }
+
+    fn get_mut(&mut self, v: &Ix) -> Option<&mut W> {
+        BTreeMap::get_mut(self, v)
+    }
+
+    fn is_empty(&self) -> bool {
+        BTreeMap::is_empty(self)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeMap::iter(self)
+    }
+}
+
+/// iter (v, w) of a vertex's adjacency map
+pub struct MapIter<'a, W, Ix = usize, A = HashMap<Ix, W>>(A::Iter<'a>)
+where
+    W: 'a,
+    Ix: 'a,
+    A: Adjacency<Ix, W> + 'a;
+
+impl<'a, W: Copy + 'a, Ix: IndexType, A: Adjacency<Ix, W>> Iterator for MapIter<'a, W, Ix, A> {
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        self.0.next().map(|(v, w)| (v.index(), *w))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// VGraph's in edges: read the reverse index if it exists, else scan every vertex
+pub enum VGraphIterTo<'a, W, Ix = usize, A = HashMap<Ix, W>>
+where
+    W: 'a,
+    Ix: 'a,
+    A: Adjacency<Ix, W> + 'a,
+{
+    Rev(MapIter<'a, W, Ix, A>),
+    Scan { lst: &'a [A], u: Ix, v: usize },
+}
+
+impl<'a, W: Copy + 'a, Ix: IndexType, A: Adjacency<Ix, W>> Iterator for VGraphIterTo<'a, W, Ix, A> {
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        match self {
+            Self::Rev(iter) => iter.next(),
+            Self::Scan { lst, u, v } => {
+                while *v < lst.len() {
+                    let cur = *v;
+                    *v += 1;
+                    if let Some(w) = lst[cur].get(u) {
+                        return Some((cur, *w));
+                    }
+                }
+                None
+            }
+        }
+    }
 }
 
 /// list of map
 /// ith HashMap means: ith vertex's all outdegrees
 /// vertex is the first-class element in this struct
-pub struct VGraph<W: Weight> {
-    lst: Vec<HashMap<usize, W>>,
+/// rev: optional reverse adjacency, ith HashMap means: ith vertex's all indegrees,
+/// with it iter_v_to/iter_e_to are O(indegree) rather than O(V)
+/// Ix: the type the keys are stored in, e.g. VGraph<W, u32> for graphs of less than 4G vertices
+/// A: the map of every vertex, HashMap by default, see SortedVGraph for BTreeMap
+/// NOTE: rev is not serialized, call build_reverse_index after deserializing if needed
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VGraph<W: Weight, Ix: IndexType = usize, A: Adjacency<Ix, W> = HashMap<Ix, W>> {
+    lst: Vec<A>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rev: Option<Vec<A>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    marker: PhantomData<(Ix, W)>,
 }
 
+/// VGraph whose out (and in) edges are always iterated in the order of their other vertex,
+/// so every algorithm gives the same result on the same graph, from run to run
+/// (rather than another valid one, e.g. bfs order, matchings, flows, tie-breaking of heaps),
+/// it's a bit slower than HashMap, use it for snapshot tests and diffed reports
+/// (its bipartite_match, disjoint path and flow methods return BTreeMap/BTreeSet, so they print the same too,
+/// the free functions return HashMap/HashSet for every graph)
+/// NOTE: CsrGraph, EGraph, MatrixGraph and DataGraph keep edges in Vec, they are deterministic too
+pub type SortedVGraph<W> = VGraph<W, usize, BTreeMap<usize, W>>;
+
 impl<W: Weight> VGraph<W> {
     pub fn new(lst: Vec<HashMap<usize, W>>) -> Self {
-        Self { lst }
+        Self::from_lst(lst)
     }
 
-    // pub fn from_rev_edges<E: Edge>(graph: &impl Graph) -> VGraph<NoWeight> {
-    // 	let n = graph.len();
-    // 	let mut lst = vec![HashMap::new(); n];
-    // 	for e in graph.iter_e_all() {
-    //         lst[e.to()].insert(e.from(), NoWeight);
-    // 	}
-    // 	VGraph::new(lst)
-    // }
+    /// keep a reverse adjacency (double the memory),
+    /// good for algorithms which use iter_v_to/iter_e_to a lot, such as dinic_new and mcmf
+    pub fn with_reverse_index(lst: Vec<HashMap<usize, W>>) -> Self {
+        let mut graph = Self::new(lst);
+        graph.build_reverse_index();
+        graph
+    }
+}
+
+impl<W: Weight> SortedVGraph<W> {
+    pub fn sorted(lst: Vec<BTreeMap<usize, W>>) -> Self {
+        Self::from_lst(lst)
+    }
+}
+
+/// every key is checked to be a vertex of the graph when deserializing
+#[cfg(feature = "serde")]
+impl<'de, W, Ix, A> serde::Deserialize<'de> for VGraph<W, Ix, A>
+where
+    W: Weight,
+    Ix: IndexType,
+    A: Adjacency<Ix, W> + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct Repr<A> {
+            lst: Vec<A>,
+        }
+
+        let Repr { lst } = Repr::<A>::deserialize(deserializer)?;
+        check_len::<Ix>(lst.len()).map_err(D::Error::custom)?;
+        for dct in lst.iter() {
+            for (v, _) in dct.iter() {
+                check_vertex(v.index(), lst.len()).map_err(D::Error::custom)?;
+            }
+        }
+        Ok(Self::from_lst(lst))
+    }
+}
+
+impl<W: Weight, Ix: IndexType, A: Adjacency<Ix, W>> VGraph<W, Ix, A> {
+    /// the same as new, for any index type and map,
+    /// return Err if there are more vertices than Ix can index
+    pub fn try_new(lst: Vec<A>) -> Result<Self, GraphError> {
+        check_len::<Ix>(lst.len())?;
+        Ok(Self::from_lst(lst))
+    }
+
+    fn from_lst(lst: Vec<A>) -> Self {
+        Self {
+            lst,
+            rev: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn build_reverse_index(&mut self) {
+        let mut rev = vec![A::default(); self.lst.len()];
+        for (u, dct) in self.lst.iter().enumerate() {
+            for (&v, &w) in dct.iter() {
+                rev[v.index()].insert(Ix::new(u), w);
+            }
+        }
+        self.rev = Some(rev);
+    }
+
+    pub fn drop_reverse_index(&mut self) {
+        self.rev = None;
+    }
+
+    pub fn has_reverse_index(&self) -> bool {
+        self.rev.is_some()
+    }
+
+    /// add an isolated vertex, return its index
+    /// NOTE: panic if there are more vertices than Ix can index, use try_add_vertex to avoid it
+    pub fn add_vertex(&mut self) -> usize {
+        self.try_add_vertex().expect("Index overflowed!")
+    }
+
+    pub fn try_add_vertex(&mut self) -> Result<usize, GraphError> {
+        check_len::<Ix>(self.lst.len() + 1)?;
+        self.lst.push(A::default());
+        if let Some(rev) = &mut self.rev {
+            rev.push(A::default());
+        }
+        Ok(self.lst.len() - 1)
+    }
+
+    /// add edge u -> v, return the old weight if the edge already existed
+    pub fn add_edge(&mut self, u: usize, v: usize, w: W) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        if let Some(rev) = &mut self.rev {
+            rev[v].insert(Ix::new(u), w);
+        }
+        Ok(self.lst[u].insert(Ix::new(v), w))
+    }
+
+    /// remove edge u -> v, return its weight if the edge existed
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        if let Some(rev) = &mut self.rev {
+            rev[v].remove(&Ix::new(u));
+        }
+        Ok(self.lst[u].remove(&Ix::new(v)))
+    }
+
+    /// change the weight of an existing edge u -> v, return the old weight
+    pub fn set_weight(&mut self, u: usize, v: usize, w: W) -> Result<W, GraphError> {
+        self.check_edge(u, v)?;
+        match self.lst[u].get_mut(&Ix::new(v)) {
+            Some(old) => {
+                if let Some(rev) = &mut self.rev {
+                    rev[v].insert(Ix::new(u), w);
+                }
+                Ok(std::mem::replace(old, w))
+            }
+            None => Err(GraphError::EdgeNotFound { from: u, to: v }),
+        }
+    }
+
+    /// remove vertex `u` and all the edges from/to it
+    /// NOTE: like Vec::remove, every vertex after `u` will be re-indexed (index - 1),
+    /// so all the indices greater than `u` you kept become invalid, it costs O(V + E)
+    pub fn remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        check_vertex(u, self.len())?;
+        remove_and_shift(&mut self.lst, u);
+        if let Some(rev) = &mut self.rev {
+            remove_and_shift(rev, u);
+        }
+        Ok(())
+    }
+
+    /// Graph::has_edge, but an out of range u or v is an error rather than a panic
+    pub fn try_has_edge(&self, u: usize, v: usize) -> Result<bool, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.lst[u].get(&Ix::new(v)).is_some())
+    }
+
+    /// Graph::weight, but an out of range u or v is an error rather than a panic
+    pub fn try_weight(&self, u: usize, v: usize) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.lst[u].get(&Ix::new(v)).cloned())
+    }
+
+    fn check_edge(&self, u: usize, v: usize) -> Result<(), GraphError> {
+        check_vertex(u, self.len())?;
+        check_vertex(v, self.len())
+    }
 }
 
-impl<W: Weight> Graph for VGraph<W> {
+/// remove `u` from the adjacency list, and re-index all the vertices after `u`
+fn remove_and_shift<W: Weight, Ix: IndexType, A: Adjacency<Ix, W>>(lst: &mut Vec<A>, u: usize) {
+    lst.remove(u);
+    let u = Ix::new(u);
+    for dct in lst.iter_mut() {
+        dct.remove(&u);
+        if dct.iter().any(|(&v, _)| v > u) {
+            *dct = std::mem::take(dct)
+                .into_iter()
+                .map(|(v, w)| if v > u { (Ix::new(v.index() - 1), w) } else { (v, w) })
+                .collect();
+        }
+    }
+}
+
+impl<W: Weight, Ix: IndexType, A: Adjacency<Ix, W>> Graph for VGraph<W, Ix, A> {
     type Weight = W;
+    type Index = Ix;
+    type IterVFrom<'a> = Vertices<MapIter<'a, W, Ix, A>> where Self: 'a;
+    type IterVTo<'a> = Vertices<VGraphIterTo<'a, W, Ix, A>> where Self: 'a;
+    type IterEFrom<'a> = MapIter<'a, W, Ix, A> where Self: 'a;
+    type IterETo<'a> = VGraphIterTo<'a, W, Ix, A> where Self: 'a;
 
     fn len(&self) -> usize {
         self.lst.len()
     }
 
-    fn iter_v_from(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-        Box::new(self.lst[u].keys().cloned())
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
     }
 
     /// it's faster than default implement, since no iterator is made?
-    fn is_empty_from(&self, u: usize) -> bool {
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
         self.lst[u].is_empty()
     }
 
-    fn iter_v_to(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-        Box::new(
-            (0..self.len())
-                .into_iter()
-                .filter(move |v| self.lst[*v].contains_key(&u)),
-        )
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        self.rev.is_some()
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        match &self.rev {
+            Some(rev) => rev[u].is_empty(),
+            None => self.iter_v_to(u).next().is_none(),
+        }
     }
 
     /// &Self::Weight -> Self::Weight, we can easily modify (not mutate) the weight
-    fn iter_e_from(&self, u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_> {
-        Box::new(self.lst[u].iter().map(move |(v, w)| (*v, *w)))
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        MapIter(self.lst[u].iter())
     }
 
     // fn iter_mut_e_from(
@@ -106,12 +555,47 @@ impl<W: Weight> Graph for VGraph<W> {
     //     Box::new(self.lst[u].iter_mut().map(move |(v, w)| (*v, w)))
     // }
 
-    /// VGraph do not need this
-    fn iter_e_to(&self, u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_> {
-        Box::new(
-            self.iter_v_to(u)
-                .map(move |v| (v, *self.lst[v].get(&u).unwrap())),
-        )
+    /// VGraph do not need this (unless it has reverse index)
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        match &self.rev {
+            Some(rev) => VGraphIterTo::Rev(MapIter(rev[u].iter())),
+            None => VGraphIterTo::Scan {
+                lst: &self.lst,
+                u: Ix::new(u),
+                v: 0,
+            },
+        }
+    }
+
+    fn edge_count(&self) -> usize {
+        self.lst.iter().map(|dct| dct.len()).sum()
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.lst[u].len()
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        match &self.rev {
+            Some(rev) => rev[u].len(),
+            None => self.iter_v_to(u).count(),
+        }
+    }
+
+    /// O(1) rather than O(outdegree)
+    fn has_edge(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.lst[u].get(&Ix::new(v)).is_some()
+    }
+
+    fn weight(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> Option<W> {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.lst[u].get(&Ix::new(v)).copied()
     }
 
     // /// VGraph do not need this
@@ -125,141 +609,291 @@ impl<W: Weight> Graph for VGraph<W> {
     // }
 }
 
-// /// edge is the first-class element in this struct,
-// /// it is not good at iter vertices (using HashSet to promise uniqueness)
-// pub struct EGraph<W, E>
-// where
-//     W: Weight,
-//     E: Edge<W>
-// {
-//     pub(crate) e_lst: Vec<E>,          // all edges
-//     pub(crate) from_v_lst: Vec<Vec<usize>>, // all edge id from v
-//     pub(crate) to_v_lst: Vec<Vec<usize>>,   // all edge id to v
-//     marker: PhantomData<W>,
-// }
-
-// impl<W, E> EGraph<W, E>
-// where
-//     W: Weight,
-//     E: Edge<W>
-// {
-//     pub fn new(e_lst: Vec<E>, n: usize) -> Self {
-// 	let mut from_v_lst = vec![vec![]; n];
-// 	let mut to_v_lst = vec![vec![]; n];
-// 	for (i, e) in e_lst.iter().enumerate() {
-// 	    from_v_lst[e.get_from()].push(i);
-// 	    to_v_lst[e.get_to()].push(i);
-// 	}
-// 	Self { e_lst, from_v_lst, to_v_lst, marker: PhantomData }
-//     }
-
-//     pub fn get_weight_mut(&mut self, i: usize) -> &mut W {
-// 	self.e_lst[i].get_weight_mut()
-//     }
-// }
-
-// pub fn len(&self) -> usize {
-// 	self.v_lst.len()
-// }
-
-//     pub fn iter_e_around(&self, u: usize) -> impl Iterator<Item = E> + '_ {
-//         self.v_lst[u].iter().map(|i| self.e_lst[*i])
-//     }
-
-//     pub fn iter_e_from(&self, u: usize) -> impl Iterator<Item = E> + '_ {
-// 	self.iter_e_around(u).filter(move |e| e.from() == u)
-//     }
-// }
-
-// impl<W, C> EGraph<W, CostFlowEdge<C, W>>
-// where
-//     W: Weight,
-//     C: Weight,
-// {
-//     /// iter cost edge
-//     pub fn iter_ce_from(&self, u: usize) -> impl Iterator<Item = (usize, usize, C)> + '_ {
-// 	self.iter_e_around(u)
-// 	    .filter(move |e| e.cap > e.flow)
-// 	    .map(move |e| {
-// 		if e.from == u {
-// 		    (u, e.to, e.cost)
-// 		} else {
-// 		    (u, e.to, e.cost)
-// 		}
-// 	    })
-//     }
-// }
-
-// impl<W, E> Graph for EGraph<W, E>
-// where
-//     W: Weight,
-//     E: Edge<W>
-// {
-//     type Weight = W;
-
-//     fn len(&self) -> usize {
-//         self.from_v_lst.len()
-//     }
-
-//     fn iter_v_from(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-// 	!unimplemented!();
-//     }
-
-//     fn iter_v_to(&self, u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-// 	!unimplemented!();
-//     }
-
-//     fn iter_e_from(&self, u: usize) -> Box<dyn Iterator<Item = (usize, &Self::Weight)> + '_> {
-//         Box::new(self.from_v_lst[u].iter().map(move |i| (self.e_lst[*i].get_to(), self.e_lst[*i].get_weight())))
-//     }
-
-//     fn iter_mut_e_from(
-//         &mut self,
-//         u: usize,
-//     ) -> Box<dyn Iterator<Item = (usize, &mut Self::Weight)> + '_> {
-// 	// Box::new(self.from_v_lst[u].clone().into_iter().map(move |i| (self.e_lst[i].get_to(), self.e_lst[i].get_weight_mut())))
-// 	Box::new(EdgeIterMut{e_lst: &mut self.e_lst, i_lst: self.from_v_lst.iter(), index: 0})
-//     }
-
-//     fn iter_e_to(&self, u: usize) -> Box<dyn Iterator<Item = (usize, &Self::Weight)> + '_> {
-//         Box::new(self.to_v_lst[u].iter().map(move |i| (self.e_lst[*i].get_to(), self.e_lst[*i].get_weight())))
-//     }
-
-//     fn iter_mut_e_to(
-//         &mut self,
-//         u: usize,
-//     ) -> Box<dyn Iterator<Item = (usize, &mut Self::Weight)> + '_> {
-// 	unimplemented!();
-//     }
-// }
-
-// struct EdgeIterMut<'a, W, E, I>
-// where
-//     W: Weight + 'a,
-//     E: Edge<W>,
-//     I: Iterator<Item = usize>,
-// {
-//     e_lst: &'a mut Vec<E>,
-//     i_lst: &'a I,
-//     index: usize,
-//     marker: PhantomData<W>
-// }
-
-// impl<'a, W, E, I> Iterator for EdgeIterMut<'a, W, E, I>
-// where
-//     W: Weight,
-//     E: Edge<W>,
-//     I: Iterator<Item = usize>,
-// {
-//     type Item = (usize, &'a mut W);
-
-//     fn next(&mut self) -> Option<Self::Item> {
-// 	while let Some(i) = self.i_lst.next() {
-// 	    return Some((self.e_lst[i].get_to(), self.e_lst[i].get_weight_mut()));
-// 	}
-// 	None
-//     }
-// }
+/// edge is the first-class element in this struct,
+/// all edges are kept in one list, every vertex keeps the ids of its in/out edges,
+/// so an edge can be visited (and mutated) by its id, that is what flow algorithms need
+/// it is also a multigraph: parallel edges are allowed, and told apart by their EdgeId
+/// NOTE: iter_v_from/iter_v_to will yield a vertex more than once, if parallel edges exist
+pub struct EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    pub(crate) e_lst: Vec<E>,               // all edges
+    pub(crate) from_v_lst: Vec<Vec<usize>>, // all edge id from v
+    pub(crate) to_v_lst: Vec<Vec<usize>>,   // all edge id to v
+    marker: PhantomData<W>,
+}
+
+/// the simplest multigraph: every edge is a (from, to, weight) tuple
+pub type MultiGraph<W> = EGraph<W, (usize, usize, W)>;
+
+impl<W, E> EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    /// `n` is the number of vertices, every edge's from/to should be less than it
+    /// the ith edge of `e_lst` will get EdgeId(i)
+    pub fn new(e_lst: Vec<E>, n: usize) -> Self {
+        let mut from_v_lst = vec![vec![]; n];
+        let mut to_v_lst = vec![vec![]; n];
+        for (i, e) in e_lst.iter().enumerate() {
+            from_v_lst[e.get_from()].push(i);
+            to_v_lst[e.get_to()].push(i);
+        }
+        Self {
+            e_lst,
+            from_v_lst,
+            to_v_lst,
+            marker: PhantomData,
+        }
+    }
+
+    /// add an isolated vertex, return its index
+    pub fn add_vertex(&mut self) -> usize {
+        self.from_v_lst.push(vec![]);
+        self.to_v_lst.push(vec![]);
+        self.from_v_lst.len() - 1
+    }
+
+    /// add an edge (parallel edges are ok), return its id
+    pub fn add_edge(&mut self, e: E) -> Result<EdgeId, GraphError> {
+        check_vertex(e.get_from(), self.len())?;
+        check_vertex(e.get_to(), self.len())?;
+        let i = self.e_lst.len();
+        self.from_v_lst[e.get_from()].push(i);
+        self.to_v_lst[e.get_to()].push(i);
+        self.e_lst.push(e);
+        Ok(EdgeId(i))
+    }
+
+    pub fn get_edge(&self, id: EdgeId) -> &E {
+        &self.e_lst[id.0]
+    }
+
+    pub fn get_edge_mut(&mut self, id: EdgeId) -> &mut E {
+        &mut self.e_lst[id.0]
+    }
+
+    pub fn get_weight_mut(&mut self, id: EdgeId) -> &mut W {
+        self.e_lst[id.0].get_weight_mut()
+    }
+
+    /// iter all the edges with their id
+    pub fn iter_edges(&self) -> impl Iterator<Item = (EdgeId, &E)> + '_ {
+        self.e_lst.iter().enumerate().map(|(i, e)| (EdgeId(i), e))
+    }
+
+    /// iter all the edges' id, which are from or to vertex `u`
+    pub fn iter_e_around(&self, u: usize) -> impl Iterator<Item = EdgeId> + '_ {
+        self.from_v_lst[u]
+            .iter()
+            .chain(self.to_v_lst[u].iter())
+            .map(|i| EdgeId(*i))
+    }
+
+    /// iter all the edges from vertex `u`: (id, to, weight)
+    pub fn iter_id_from(&self, u: usize) -> impl Iterator<Item = (EdgeId, usize, W)> + '_ {
+        self.from_v_lst[u].iter().map(move |i| {
+            let e = &self.e_lst[*i];
+            (EdgeId(*i), e.get_to(), *e.get_weight())
+        })
+    }
+
+    /// iter all the edges to vertex `u`: (id, from, weight)
+    pub fn iter_id_to(&self, u: usize) -> impl Iterator<Item = (EdgeId, usize, W)> + '_ {
+        self.to_v_lst[u].iter().map(move |i| {
+            let e = &self.e_lst[*i];
+            (EdgeId(*i), e.get_from(), *e.get_weight())
+        })
+    }
+}
+
+impl<W: Weight> EGraph<W, FlowEdge<W>> {
+    pub fn get_flow(&self, id: EdgeId) -> W {
+        self.e_lst[id.0].flow
+    }
+
+    pub fn get_flow_mut(&mut self, id: EdgeId) -> &mut W {
+        &mut self.e_lst[id.0].flow
+    }
+
+    /// iter the flow of every edge: (id, flow)
+    pub fn iter_flow(&self) -> impl Iterator<Item = (EdgeId, W)> + '_ {
+        self.e_lst.iter().enumerate().map(|(i, e)| (EdgeId(i), e.flow))
+    }
+
+    /// set all edges' flow to zero, then we can run another flow algorithm
+    pub fn reset_flow(&mut self) {
+        for e in self.e_lst.iter_mut() {
+            e.flow = Default::default();
+        }
+    }
+}
+
+impl<C: Weight, W: Weight> EGraph<C, CostFlowEdge<C, W>> {
+    pub fn get_flow(&self, id: EdgeId) -> W {
+        self.e_lst[id.0].flow
+    }
+
+    /// iter the flow of every edge: (id, flow)
+    pub fn iter_flow(&self) -> impl Iterator<Item = (EdgeId, W)> + '_ {
+        self.e_lst.iter().enumerate().map(|(i, e)| (EdgeId(i), e.flow))
+    }
+
+    /// set all edges' flow to zero, then we can run another flow algorithm
+    pub fn reset_flow(&mut self) {
+        for e in self.e_lst.iter_mut() {
+            e.flow = Default::default();
+        }
+    }
+}
+
+/// serialized as {e_lst, n}, the edge ids of every vertex are rebuilt (and checked) when deserializing
+#[cfg(feature = "serde")]
+impl<W, E> serde::Serialize for EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W> + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("EGraph", 2)?;
+        state.serialize_field("e_lst", &self.e_lst)?;
+        state.serialize_field("n", &self.len())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, W, E> serde::Deserialize<'de> for EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W> + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct Repr<E> {
+            e_lst: Vec<E>,
+            n: usize,
+        }
+
+        let Repr { e_lst, n } = Repr::<E>::deserialize(deserializer)?;
+        for e in e_lst.iter() {
+            check_vertex(e.get_from(), n).map_err(D::Error::custom)?;
+            check_vertex(e.get_to(), n).map_err(D::Error::custom)?;
+        }
+        Ok(Self::new(e_lst, n))
+    }
+}
+
+/// EGraph's edges of a vertex, by their ids
+/// forward: yield (to, w) of out edges, else (from, w) of in edges
+pub struct EGraphIter<'a, W, E> {
+    ids: std::slice::Iter<'a, usize>,
+    e_lst: &'a [E],
+    forward: bool,
+    marker: PhantomData<W>,
+}
+
+impl<'a, W, E> Iterator for EGraphIter<'a, W, E>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        self.ids.next().map(|i| {
+            let e = &self.e_lst[*i];
+            let v = if self.forward { e.get_to() } else { e.get_from() };
+            (v, *e.get_weight())
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<W, E> Graph for EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    type Weight = W;
+    type Index = usize;
+    type IterVFrom<'a> = Vertices<EGraphIter<'a, W, E>> where Self: 'a;
+    type IterVTo<'a> = Vertices<EGraphIter<'a, W, E>> where Self: 'a;
+    type IterEFrom<'a> = EGraphIter<'a, W, E> where Self: 'a;
+    type IterETo<'a> = EGraphIter<'a, W, E> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.from_v_lst.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.from_v_lst[u].is_empty()
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.to_v_lst[u].is_empty()
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        EGraphIter {
+            ids: self.from_v_lst[u].iter(),
+            e_lst: &self.e_lst,
+            forward: true,
+            marker: PhantomData,
+        }
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        EGraphIter {
+            ids: self.to_v_lst[u].iter(),
+            e_lst: &self.e_lst,
+            forward: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// parallel edges are all counted
+    fn edge_count(&self) -> usize {
+        self.e_lst.len()
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.from_v_lst[u].len()
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.to_v_lst[u].len()
+    }
+}
 
 /// A macro to make vertices from 0..n
 #[macro_export]
@@ -327,6 +961,163 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reverse_index() {
+        let (g, _s_lst) = MakeGraph::mf();
+        let mut g2 = VGraph::with_reverse_index(g.lst.clone());
+        assert!(g2.has_reverse_index());
+        for u in 0..g.len() {
+            let mut lhs: Vec<(usize, i32)> = g.iter_e_to(u).collect();
+            let mut rhs: Vec<(usize, i32)> = g2.iter_e_to(u).collect();
+            lhs.sort();
+            rhs.sort();
+            assert_eq!(lhs, rhs);
+            assert_eq!(g.is_empty_to(u), g2.is_empty_to(u));
+        }
+
+        let flowing = crate::dinic_new::dinic(&g2, 0, 5);
+        assert_eq!(flowing.get(&5).unwrap().values().sum::<i32>(), 23);
+
+        g2.drop_reverse_index();
+        assert!(!g2.has_reverse_index());
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut g = VGraph::with_reverse_index(vec![HashMap::new(); 2]);
+        let c = g.add_vertex();
+        assert_eq!(c, 2);
+        assert_eq!(g.add_edge(0, 1, 3), Ok(None));
+        assert_eq!(g.add_edge(0, 1, 4), Ok(Some(3)));
+        assert_eq!(g.add_edge(1, c, 5), Ok(None));
+        assert_eq!(g.add_edge(c, 0, 6), Ok(None));
+        assert_eq!(g.try_has_edge(0, 1), Ok(true));
+        assert_eq!(g.try_weight(0, 1), Ok(Some(4)));
+        assert_eq!(g.set_weight(0, 1, 1), Ok(4));
+        assert_eq!(g.iter_e_to(1).collect::<Vec<_>>(), vec![(0, 1)]);
+        assert_eq!(g.set_weight(1, 0, 1), Err(GraphError::EdgeNotFound { from: 1, to: 0 }));
+        assert_eq!(
+            g.add_edge(0, 3, 1),
+            Err(GraphError::VertexOutOfRange { index: 3, len: 3 })
+        );
+        assert_eq!(g.remove_edge(0, 1), Ok(Some(1)));
+        assert_eq!(g.try_weight(0, 1), Ok(None));
+        assert!(g.is_empty_to(1));
+
+        // c -> 0 will be 1 -> 0 after removing vertex 1
+        g.remove_vertex(1).unwrap();
+        assert_eq!(g.len(), 2);
+        assert_eq!(g.try_weight(1, 0), Ok(Some(6)));
+        assert_eq!(g.iter_v_to(0).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(
+            g.remove_vertex(2),
+            Err(GraphError::VertexOutOfRange { index: 2, len: 2 })
+        );
+    }
+
+    #[test]
+    fn test_multigraph() {
+        let mut g: MultiGraph<i32> = EGraph::new(vec![], 2);
+        let a = g.add_edge((0, 1, 3)).unwrap();
+        let b = g.add_edge((0, 1, 4)).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(
+            g.iter_id_from(0).collect::<Vec<_>>(),
+            vec![(a, 1, 3), (b, 1, 4)]
+        );
+        assert_eq!(g.iter_id_to(1).map(|e| e.0).collect::<Vec<_>>(), vec![a, b]);
+        *g.get_weight_mut(b) = 5;
+        assert_eq!(*g.get_edge(b).get_weight(), 5);
+        assert_eq!(
+            g.add_edge((0, 2, 1)),
+            Err(GraphError::VertexOutOfRange { index: 2, len: 2 })
+        );
+        let c = g.add_vertex();
+        assert_eq!(g.add_edge((1, c, 1)), Ok(EdgeId(2)));
+        assert_eq!(g.iter_e_around(1).collect::<Vec<_>>(), vec![EdgeId(2), a, b]);
+    }
+
+    #[test]
+    fn test_sorted_vgraph() {
+        use crate::{bfs, bipartite_match, dfs, edmonds_karp, scc, GraphBuilder};
+
+        // the same graph, built from edges in different orders
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let mut edges: Vec<(usize, usize, i32)> = g.iter_e_all().collect();
+        let g1 = GraphBuilder::new(g.len()).edges(edges.clone()).build_sorted_vgraph().unwrap();
+        edges.reverse();
+        let mut g2 = GraphBuilder::new(g.len()).edges(edges).build_sorted_vgraph().unwrap();
+        g2.build_reverse_index();
+
+        assert_eq!(g1.iter_v_from(7).collect::<Vec<_>>(), vec![0, 1, 6, 8]);
+        assert_eq!(g2.iter_v_to(7).collect::<Vec<_>>(), vec![0, 1, 6, 8]);
+        assert_eq!(bfs::bfs(&g1, 0).collect::<Vec<_>>(), bfs::bfs(&g2, 0).collect::<Vec<_>>());
+        assert_eq!(dfs::dfs(&g1, 4).collect::<Vec<_>>(), dfs::dfs(&g2, 4).collect::<Vec<_>>());
+        assert_eq!(scc::scc(&g1), scc::scc(&g2));
+        assert_eq!(bipartite_match::bipartite_match(&g1), bipartite_match::bipartite_match(&g2));
+        assert_eq!(edmonds_karp::edmonds_karp(&g1, 0, 4), edmonds_karp::edmonds_karp(&g2, 0, 4));
+        assert_eq!(g1.dijkstra(0).collect::<Vec<_>>(), g2.dijkstra(0).collect::<Vec<_>>());
+        assert_eq!(g1.kruskal(), g2.kruskal());
+    }
+
+    #[test]
+    fn test_sorted_vgraph_format() {
+        use crate::{GraphBuilder, NoWeight};
+
+        fn sorted<W: Weight>(g: &VGraph<W>, rev: bool) -> SortedVGraph<W> {
+            let mut edges: Vec<(usize, usize, W)> = g.iter_e_all().collect();
+            if rev {
+                edges.reverse();
+            }
+            GraphBuilder::new(g.len()).edges(edges).build_sorted_vgraph().unwrap()
+        }
+
+        let run = |rev: bool| {
+            let mbm: SortedVGraph<NoWeight> = sorted(&MakeGraph::mbm().0, rev);
+            let dp: SortedVGraph<NoWeight> = sorted(&MakeGraph::dp().0, rev);
+            let mf: SortedVGraph<i32> = sorted(&MakeGraph::mf().0, rev);
+            format!(
+                "{:?} {:?} {:?} {:?} {:?}",
+                mbm.bipartite_match(),
+                dp.vertex_disjoint_path(0, 6),
+                dp.edge_disjoint_path(0, 6),
+                mf.edmonds_karp(0, 5),
+                mf.dinic(0, 5),
+            )
+        };
+        assert_eq!(run(false), run(false));
+        assert_eq!(run(false), run(true));
+    }
+
+    #[test]
+    fn test_query() {
+        use crate::{CsrGraph, MatrixGraph};
+
+        let (mut g, _s_lst) = MakeGraph::mst(true);
+        g.build_reverse_index();
+        let csr = CsrGraph::from_graph(&g);
+        let mat = MatrixGraph::from(&g);
+        let lhs: Vec<_> = g.iter_e_all().collect();
+        assert_eq!(lhs.len(), g.edge_count());
+        assert_eq!(csr.edge_count(), g.edge_count());
+        assert_eq!(mat.edge_count(), g.edge_count());
+        assert_eq!(g.out_degree(7), 4);
+        assert_eq!(csr.in_degree(7), 4);
+        assert_eq!(mat.in_degree(7), g.in_degree(7));
+        for (u, v, w) in lhs {
+            assert!(csr.has_edge(u, v) && mat.has_edge(u, v));
+            assert_eq!(csr.weight(u, v), Some(w));
+            assert_eq!(mat.weight(u, v), g.weight(u, v));
+        }
+        assert!(!g.has_edge(0, 4));
+        assert_eq!(csr.weight(0, 4), None);
+        let mut rhs: Vec<_> = csr.iter_e_all().collect();
+        rhs.sort();
+        let mut lhs: Vec<_> = g.iter_e_all().collect();
+        lhs.sort();
+        assert_eq!(lhs, rhs);
+    }
+
     #[allow(unused_variables)]
     #[test]
     fn test_macro() {
@@ -0,0 +1,453 @@
+/// priority queues used by the algorithms, all of them are min-heaps over PartialOrd
+/// Heap: binary heap, the same item can be pushed many times (for lazy deletion)
+/// IndexedHeap: d-ary heap of (index, key), an index is in the heap at most once,
+/// so its key can be decreased in place, and the size is bounded by the number of indices
+/// PairingHeap: O(1) push and meld, amortized O(logn) pop
+use crate::IndexType;
+use std::mem;
+
+pub trait PriorityQueue<T> {
+    fn push(&mut self, item: T);
+
+    /// pop the minimum
+    fn pop(&mut self) -> Option<T>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct Heap<T: PartialOrd> {
+    data: Vec<T>,
+}
+
+impl<T: PartialOrd> Heap<T> {
+    pub fn new() -> Self {
+        Self { data: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.data.push(item);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let res = self.data.swap_remove(0);
+        self.sift_down(0);
+        Some(res)
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let p = (i - 1) / 2;
+            if self.data[i] < self.data[p] {
+                self.data.swap(i, p);
+                i = p;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        let n = self.data.len();
+        loop {
+            let mut m = i;
+            for c in [2 * i + 1, 2 * i + 2] {
+                if c < n && self.data[c] < self.data[m] {
+                    m = c;
+                }
+            }
+            if m == i {
+                break;
+            }
+            self.data.swap(i, m);
+            i = m;
+        }
+    }
+}
+
+impl<T: PartialOrd> Default for Heap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// heapify, O(n)
+impl<T: PartialOrd> From<Vec<T>> for Heap<T> {
+    fn from(data: Vec<T>) -> Self {
+        let mut heap = Self { data };
+        for i in (0..heap.data.len() / 2).rev() {
+            heap.sift_down(i);
+        }
+        heap
+    }
+}
+
+impl<T: PartialOrd> PriorityQueue<T> for Heap<T> {
+    fn push(&mut self, item: T) {
+        Heap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Heap::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// indices can be any usize, but pos costs O(max index) memory,
+/// so use it on vertices (0..n)
+/// indices and positions are stored in Ix (see IndexType), Ix::end() means not in the heap
+pub struct IndexedHeap<K: PartialOrd, Ix: IndexType = usize> {
+    arity: usize,
+    data: Vec<(Ix, K)>,
+    pos: Vec<Ix>, // pos[i]: where index i is in data, Ix::end() if not in
+}
+
+impl<K: PartialOrd> IndexedHeap<K> {
+    /// binary heap for indices 0..n (it grows if a larger index is pushed)
+    pub fn new(n: usize) -> Self {
+        Self::with_arity(n, 2)
+    }
+
+    /// d-ary heap, larger arity means cheaper decrease_key but more expensive pop
+    /// NOTE: panic if arity < 2
+    pub fn with_arity(n: usize, arity: usize) -> Self {
+        Self::with_index(n, arity)
+    }
+}
+
+impl<K: PartialOrd, Ix: IndexType> IndexedHeap<K, Ix> {
+    /// with_arity, but indices are stored in Ix, e.g. G::Index of the graph,
+    /// NOTE: indices should not be greater than Ix::MAX_LEN
+    pub fn with_index(n: usize, arity: usize) -> Self {
+        assert!(arity >= 2, "arity should be at least 2");
+        Self {
+            arity,
+            data: vec![],
+            pos: vec![Ix::end(); n],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.pos.len() && self.pos[i] != Ix::end()
+    }
+
+    /// the key of index `i`, None if not in the heap
+    pub fn get(&self, i: usize) -> Option<&K> {
+        if self.contains(i) {
+            Some(&self.data[self.pos[i].index()].1)
+        } else {
+            None
+        }
+    }
+
+    pub fn peek(&self) -> Option<(usize, &K)> {
+        self.data.first().map(|(i, k)| (i.index(), k))
+    }
+
+    /// insert index `i`, or replace its key (larger or smaller) if it's already in
+    pub fn push(&mut self, i: usize, key: K) {
+        if i >= self.pos.len() {
+            self.pos.resize(i + 1, Ix::end());
+        }
+        if self.contains(i) {
+            let p = self.pos[i].index();
+            let up = key < self.data[p].1;
+            self.data[p].1 = key;
+            if up {
+                self.sift_up(p);
+            } else {
+                self.sift_down(p);
+            }
+        } else {
+            self.data.push((Ix::new(i), key));
+            self.pos[i] = Ix::new(self.data.len() - 1);
+            self.sift_up(self.data.len() - 1);
+        }
+    }
+
+    /// insert index `i` if it's not in, or decrease its key if `key` is smaller
+    /// return true if the heap is changed
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    pub fn decrease_key(&mut self, i: usize, key: K) -> bool {
+        match self.get(i) {
+            Some(old) if !(key < *old) => false,
+            _ => {
+                self.push(i, key);
+                true
+            }
+        }
+    }
+
+    /// pop the (index, key) with the minimum key
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let (i, key) = self.data.swap_remove(0);
+        self.pos[i.index()] = Ix::end();
+        if !self.data.is_empty() {
+            self.pos[self.data[0].0.index()] = Ix::new(0);
+            self.sift_down(0);
+        }
+        Some((i.index(), key))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+        self.pos[self.data[a].0.index()] = Ix::new(a);
+        self.pos[self.data[b].0.index()] = Ix::new(b);
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let p = (i - 1) / self.arity;
+            if self.data[i].1 < self.data[p].1 {
+                self.swap(i, p);
+                i = p;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        let n = self.data.len();
+        loop {
+            let mut m = i;
+            let first = self.arity * i + 1;
+            for c in first..n.min(first + self.arity) {
+                if self.data[c].1 < self.data[m].1 {
+                    m = c;
+                }
+            }
+            if m == i {
+                break;
+            }
+            self.swap(i, m);
+            i = m;
+        }
+    }
+}
+
+struct Node<T> {
+    item: T,
+    children: Vec<Box<Node<T>>>,
+}
+
+pub struct PairingHeap<T: PartialOrd> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+}
+
+impl<T: PartialOrd> PairingHeap<T> {
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.item)
+    }
+
+    pub fn push(&mut self, item: T) {
+        let node = Box::new(Node {
+            item,
+            children: vec![],
+        });
+        self.root = Some(match self.root.take() {
+            Some(root) => Self::merge(root, node),
+            None => node,
+        });
+        self.len += 1;
+    }
+
+    /// move all the items of `other` into self, O(1)
+    pub fn meld(&mut self, mut other: Self) {
+        self.len += other.len;
+        other.len = 0;
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(Self::merge(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// two pass: merge children in pairs from left to right, then merge them from right to left
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let Node { item, children } = *root;
+        let mut pairs = Vec::with_capacity(children.len() / 2 + 1);
+        let mut iter = children.into_iter();
+        while let Some(a) = iter.next() {
+            pairs.push(match iter.next() {
+                Some(b) => Self::merge(a, b),
+                None => a,
+            });
+        }
+        self.root = pairs.into_iter().rev().reduce(|acc, x| Self::merge(acc, x));
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn merge(mut a: Box<Node<T>>, mut b: Box<Node<T>>) -> Box<Node<T>> {
+        if b.item < a.item {
+            mem::swap(&mut a, &mut b);
+        }
+        a.children.push(b);
+        a
+    }
+}
+
+impl<T: PartialOrd> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// the tree can be very deep (e.g. push a decreasing sequence),
+/// so drop it without recursion
+impl<T: PartialOrd> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T: PartialOrd> PriorityQueue<T> for PairingHeap<T> {
+    fn push(&mut self, item: T) {
+        PairingHeap::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// xorshift, so no rand is needed
+    fn random_lst(n: usize) -> Vec<u32> {
+        let mut x: u32 = 2463534242;
+        (0..n)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x % 1000
+            })
+            .collect()
+    }
+
+    fn drain<Q: PriorityQueue<u32>>(mut queue: Q, lst: &[u32]) -> Vec<u32> {
+        let n = queue.len();
+        for x in lst.iter() {
+            queue.push(*x);
+        }
+        assert_eq!(queue.len(), n + lst.len());
+        let mut res = vec![];
+        while let Some(x) = queue.pop() {
+            res.push(x);
+        }
+        assert!(queue.is_empty());
+        res
+    }
+
+    #[test]
+    fn test_heap() {
+        let lst = random_lst(1000);
+        let mut sorted = lst.clone();
+        sorted.sort();
+        assert_eq!(drain(Heap::new(), &lst), sorted);
+        assert_eq!(drain(PairingHeap::new(), &lst), sorted);
+        assert_eq!(drain(Heap::from(lst.clone()), &[]), sorted);
+
+        let mut a = PairingHeap::new();
+        let mut b = PairingHeap::new();
+        for (i, x) in lst.iter().enumerate() {
+            if i % 2 == 0 { a.push(*x) } else { b.push(*x) }
+        }
+        a.meld(b);
+        assert_eq!(drain(a, &[]), sorted);
+
+        // deep tree should not overflow the stack when dropped
+        let mut deep = PairingHeap::new();
+        for x in (0..1_000_000).rev() {
+            deep.push(x);
+        }
+    }
+
+    #[test]
+    fn test_indexed_heap() {
+        for arity in [2, 3, 4] {
+            let lst = random_lst(500);
+            let mut heap = IndexedHeap::with_arity(0, arity);
+            let mut best = vec![u32::MAX; 100];
+            for (j, x) in lst.iter().enumerate() {
+                let i = j % 100;
+                assert_eq!(heap.decrease_key(i, *x), *x < best[i]);
+                best[i] = best[i].min(*x);
+            }
+            assert_eq!(heap.len(), 100);
+            assert_eq!(heap.get(7), Some(&best[7]));
+
+            heap.push(3, 2000); // increase
+            best[3] = 2000;
+            let mut expected: Vec<(u32, usize)> = best.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+            expected.sort();
+            let mut res = vec![];
+            while let Some((i, k)) = heap.pop() {
+                assert!(!heap.contains(i));
+                res.push((k, i));
+            }
+            assert_eq!(
+                res.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+                expected.iter().map(|(k, _)| *k).collect::<Vec<_>>()
+            );
+            assert_eq!(res.last(), Some(&(2000, 3)));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+/// the integer type a graph stores its vertex indices in,
+/// u32 halves the memory of usize (on 64-bit) for graphs of less than 4G vertices
+/// it's used by the containers (VGraph, CsrGraph) and the per-vertex state of algorithms
+/// (from, queue, stack, heap entries), the api still takes and returns usize / VertexId
+use crate::{GraphError, VertexId};
+use std::fmt;
+use std::hash::Hash;
+
+pub trait IndexType: Copy + Default + Ord + Hash + fmt::Debug + Send + Sync + 'static {
+    /// the max value is reserved as a sentinel, so a graph has at most MAX_LEN vertices
+    const MAX_LEN: usize;
+
+    /// NOTE: `x` should not be greater than MAX_LEN, it's only checked in debug build
+    fn new(x: usize) -> Self;
+
+    fn index(self) -> usize;
+
+    /// the sentinel, never a vertex
+    fn end() -> Self {
+        Self::new(Self::MAX_LEN)
+    }
+}
+
+impl IndexType for usize {
+    const MAX_LEN: usize = usize::MAX;
+
+    fn new(x: usize) -> Self {
+        x
+    }
+
+    fn index(self) -> usize {
+        self
+    }
+}
+
+impl IndexType for u32 {
+    const MAX_LEN: usize = u32::MAX as usize;
+
+    fn new(x: usize) -> Self {
+        debug_assert!(x <= Self::MAX_LEN);
+        x as u32
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl IndexType for u16 {
+    const MAX_LEN: usize = u16::MAX as usize;
+
+    fn new(x: usize) -> Self {
+        debug_assert!(x <= Self::MAX_LEN);
+        x as u16
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// helper to check a graph of `len` vertices can be indexed by Ix
+pub(crate) fn check_len<Ix: IndexType>(len: usize) -> Result<(), GraphError> {
+    if len <= Ix::MAX_LEN {
+        Ok(())
+    } else {
+        Err(GraphError::IndexOverflow { len, max: Ix::MAX_LEN })
+    }
+}
+
+/// per-vertex state (e.g. from) of an algorithm, as it's returned by the api
+pub(crate) fn vertex_ids<Ix: IndexType>(lst: Vec<Ix>) -> Vec<VertexId> {
+    lst.into_iter().map(|u| VertexId(u.index())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{a_star, bellman_ford, bfs, dfs, dijkstra, prim, scc, spfa};
+    use crate::{CsrGraph, Graph, IndexedHeap, MakeGraph, VGraph};
+    use std::collections::HashMap;
+
+    /// copy a testing graph into one of another index type
+    fn compact<Ix: IndexType>(g: &VGraph<i32>) -> Result<VGraph<i32, Ix>, GraphError> {
+        let lst = (0..g.len())
+            .map(|u| g.iter_e_from(u).map(|(v, w)| (Ix::new(v), w)).collect())
+            .collect();
+        VGraph::try_new(lst)
+    }
+
+    #[test]
+    fn test_index() {
+        assert_eq!(u32::new(7).index(), 7);
+        assert_eq!(u16::end().index(), 65535);
+        assert_eq!(check_len::<u16>(65535), Ok(()));
+        assert_eq!(check_len::<u16>(65536), Err(GraphError::IndexOverflow { len: 65536, max: 65535 }));
+
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let g2 = compact::<u32>(&g).unwrap();
+        let csr = CsrGraph::<i32, u16>::try_from_graph(&g).unwrap();
+        assert_eq!(bfs::bfs(&g2, 0).collect::<Vec<_>>().len(), g.len());
+        assert_eq!(dfs::dfs(&csr, 0).count(), g.len());
+        assert_eq!(scc::scc(&g2).len(), scc::scc(&g).len());
+        let sum = |lst: Vec<Option<i32>>| lst.into_iter().flatten().sum::<i32>();
+        assert_eq!(sum(spfa::spfa(&g2, 3).1), sum(spfa::spfa(&g, 3).1));
+        let lhs: i32 = dijkstra::dijkstra(&g, 3).map(|e| e.dist).sum();
+        assert_eq!(dijkstra::dijkstra(&g2, 3).map(|e| e.dist).sum::<i32>(), lhs);
+        assert_eq!(dijkstra::dijkstra(&csr, 3).map(|e| e.dist).sum::<i32>(), lhs);
+        assert_eq!(spfa::spfa(&g2, 3).2, spfa::spfa(&g, 3).2);
+        assert_eq!(bellman_ford::bellman_ford(&csr, 3).2, bellman_ford::bellman_ford(&g, 3).2);
+        assert_eq!(prim::prim(&g2), prim::prim(&g));
+        let h = |_| 0;
+        assert_eq!(a_star::a_star(&g2, 3, h).collect::<Vec<_>>(), a_star::a_star(&g, 3, h).collect::<Vec<_>>());
+
+        let mut heap: IndexedHeap<i32, u16> = IndexedHeap::with_index(4, 2);
+        heap.push(3, 5);
+        heap.push(1, 7);
+        assert!(heap.decrease_key(1, 2));
+        assert_eq!((heap.pop(), heap.pop(), heap.pop()), (Some((1, 2)), Some((3, 5)), None));
+
+        let mut g3: VGraph<i32, u16> = VGraph::try_new(vec![HashMap::new(); 65535]).unwrap();
+        assert_eq!(g3.try_add_vertex(), Err(GraphError::IndexOverflow { len: 65536, max: 65535 }));
+        assert_eq!(g3.add_edge(65534, 0, 1), Ok(None));
+        assert_eq!(g3.iter_v_from(65534).collect::<Vec<_>>(), vec![0]);
+        assert!(VGraph::<i32, u16>::try_new(vec![HashMap::new(); 65536]).is_err());
+    }
+}
@@ -1,35 +1,79 @@
-use crate::{Graph, VGraph};
+use crate::dijkstra::try_dijkstra;
+use crate::view::MapWeight;
+use crate::{Graph, GraphError, VertexId, Weight};
 
-use std::collections::HashMap;
+/// NOTE: panic if negative cycle existed or overflowed, use try_johnson to avoid it
+#[allow(clippy::type_complexity)]
+pub fn johnson<G: Graph>(graph: &G) -> Vec<(Vec<Option<G::Weight>>, Vec<VertexId>)> {
+    try_johnson(graph).expect("Negative cycle found or overflowed!")
+}
 
 #[allow(clippy::type_complexity)]
-pub fn johnson<G: Graph>(graph: &G) -> Vec<(Vec<Option<G::Weight>>, Vec<usize>)> {
+pub fn try_johnson<G: Graph>(
+    graph: &G,
+) -> Result<Vec<(Vec<Option<G::Weight>>, Vec<VertexId>)>, GraphError> {
     let n = graph.len();
-    let h = calc_h(graph).expect("Negative cycle found!");
-
-    let mut lst = Vec::with_capacity(n);
-    for u in 0..n {
-        let mut map = HashMap::new();
-        // no size hint, may a bit slower than using the special structure such as VGraph
-        for (v, w) in graph.iter_e_from(u) {
-            map.insert(v, w + h[u] - h[v]);
-        }
-        lst.insert(u, map);
-    }
+    let h = calc_h(graph)?;
+    check_reweight(graph, &h)?;
 
-    let g2 = VGraph::new(lst);
+    // reweight on the fly, no need to copy the graph
+    let g2 = MapWeight::new(graph, |u, v, w| reweight(w, h[u], h[v]).unwrap()); // checked
 
-    let mut res = Vec::with_capacity(n); // : Vec<(Vec<Option<W>>, Vec<usize>)>
-    for i in 0..n {
-        let mut dist = vec![None; n];
-        let mut from = vec![i; n];
-        for (d, u, v) in g2.dijkstra(i) {
-            dist[u] = Some(d - h[i] + h[u]);
-            from[u] = v;
-        }
-        res.push((dist, from));
+    (0..n).map(|i| johnson_from(&g2, &h, i)).collect()
+}
+
+/// w + h[u] - h[v], which is never negative, None if overflowed
+pub(crate) fn reweight<W: Weight>(w: W, hu: W, hv: W) -> Option<W> {
+    w.checked_add(hu)?.checked_sub(hv)
+}
+
+/// Err(Overflow) if some reweighted edge can not be held by the weight type,
+/// then it's ok to unwrap reweight on every edge
+pub(crate) fn check_reweight<G: Graph>(graph: &G, h: &[G::Weight]) -> Result<(), GraphError> {
+    for (u, v, w) in graph.iter_e_all() {
+        reweight(w, h[u], h[v]).ok_or(GraphError::Overflow)?;
+    }
+    Ok(())
+}
+
+/// dijkstra from `i` on the reweighted graph `g2`, then weight the distances back
+#[allow(clippy::type_complexity)]
+pub(crate) fn johnson_from<G: Graph>(
+    g2: &G,
+    h: &[G::Weight],
+    i: usize,
+) -> Result<(Vec<Option<G::Weight>>, Vec<VertexId>), GraphError> {
+    let n = g2.len();
+    let mut dist = vec![None; n];
+    let mut from = vec![VertexId(i); n];
+    for e in try_dijkstra(g2, i)? {
+        let e = e?;
+        let u = e.to.index();
+        // dist + h[u] is between the real distance and dist, only the last step may overflow
+        let d = e.dist.checked_add(h[u]).and_then(|d| d.checked_sub(h[i]));
+        dist[u] = Some(d.ok_or(GraphError::Overflow)?);
+        from[u] = e.from;
     }
-    res
+    Ok((dist, from))
+}
+
+/// distances are accumulated in D rather than G::Weight (see dijkstra_as)
+#[allow(clippy::type_complexity)]
+pub fn johnson_as<G, D>(graph: &G) -> Vec<(Vec<Option<D>>, Vec<VertexId>)>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    johnson(&MapWeight::new(graph, |_, _, w| D::from(w)))
+}
+
+#[allow(clippy::type_complexity)]
+pub fn try_johnson_as<G, D>(graph: &G) -> Result<Vec<(Vec<Option<D>>, Vec<VertexId>)>, GraphError>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    try_johnson(&MapWeight::new(graph, |_, _, w| D::from(w)))
 }
 
 /// use modified (faster) bellman ford, to remove negative edges
@@ -37,51 +81,78 @@ pub fn johnson<G: Graph>(graph: &G) -> Vec<(Vec<Option<G::Weight>>, Vec<usize>)>
 /// 2. no need to add an additional vertex (johnson)
 /// 3. the if condition is much easier
 /// 4. the super vertex always have an edge to every vertex, so no use to use Option
+///
 /// O(V(E+V)logV)
-fn calc_h<G: Graph>(graph: &G) -> Option<Vec<G::Weight>> {
+pub(crate) fn calc_h<G: Graph>(graph: &G) -> Result<Vec<G::Weight>, GraphError> {
     let n = graph.len();
-    let mut dist = vec![Default::default(); n];
+    let mut dist: Vec<G::Weight> = vec![Default::default(); n];
 
     for _ in 0..n {
         let mut improved = false;
         for u in 0..n {
             for (v, w) in graph.iter_e_from(u) {
-                if dist[u] + w < dist[v] {
-                    dist[v] = dist[u] + w;
+                let d = dist[u].checked_add(w).ok_or(GraphError::Overflow)?;
+                if d < dist[v] {
+                    dist[v] = d;
                     improved = true;
                 }
             }
         }
         if !improved {
-            return Some(dist);
+            return Ok(dist);
         }
     }
-    None
+    Err(GraphError::NegativeCycle)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, VGraph};
+    use std::collections::HashMap;
 
     #[test]
     fn test_johnson() {
         let (g, s_lst) = MakeGraph::spn();
         for (u, (dist, from)) in g.johnson().into_iter().enumerate() {
             println!("distances from: {}", s_lst[u]);
-            for (i, (od, v)) in dist.into_iter().zip(from.into_iter()).enumerate() {
+            for (i, (od, v)) in dist.into_iter().zip(from).enumerate() {
                 if i != u {
                     match od {
                         Some(d) => println!(
                             "to: {}, directly from: {}, distance: {}",
-                            s_lst[i], s_lst[v], d
+                            s_lst[i], s_lst[v.index()], d
                         ),
                         None => println!(
                             "to: {}, directly from: {}, distance: inf",
-                            s_lst[i], s_lst[v]
+                            s_lst[i], s_lst[v.index()]
                         ),
                     }
                 }
             }
         }
     }
+
+    #[test]
+    fn test_try_johnson() {
+        let g = VGraph::new(vec![HashMap::from([(1, 1)]), HashMap::from([(0, -2)])]);
+        assert_eq!(try_johnson(&g), Err(GraphError::NegativeCycle));
+        let (g, _s_lst) = MakeGraph::spn();
+        assert_eq!(try_johnson(&g).unwrap(), g.johnson());
+        let lhs: Vec<Vec<Option<i64>>> = g
+            .johnson()
+            .into_iter()
+            .map(|(dist, _)| dist.into_iter().map(|d| d.map(i64::from)).collect())
+            .collect();
+        let rhs: Vec<Vec<Option<i64>>> = try_johnson_as(&g).unwrap().into_iter().map(|(dist, _)| dist).collect();
+        assert_eq!(lhs, rhs);
+
+        let g = VGraph::new(vec![HashMap::from([(1, 100i8)]), HashMap::from([(2, 100)]), HashMap::new()]);
+        assert_eq!(johnson_as::<_, i32>(&g)[0].0, vec![None, Some(100), Some(200)]);
+
+        // the real distances fit in i8, but the reweighted edge 0 -> 2 (100 + 0 + 127) does not
+        let g = VGraph::new(vec![HashMap::from([(1, -100i8), (2, 100)]), HashMap::from([(2, -27)]), HashMap::new()]);
+        assert_eq!(try_johnson(&g), Err(GraphError::Overflow));
+        assert_eq!(johnson_as::<_, i16>(&g)[0].0, vec![None, Some(-100), Some(-127)]);
+    }
 }
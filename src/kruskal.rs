@@ -1,28 +1,37 @@
-use crate::Graph;
+use crate::{Graph, GraphError, TreeEdge, Weight};
 use std::cmp::Ordering;
 //use std::collections::BinaryHeap;
-use utils::Heap;
+use crate::heap::Heap;
 
 /// NOTE: kruskal can process directed graph (which will be more efficient)
 /// since we need the spanning tree, return Vec rather Iterator
 /// O(ElogV)
-pub fn kruskal<G: Graph>(graph: &G) -> Vec<(G::Weight, usize, usize)> {
-    let n = graph.len();
-    let mut heap = vec![];
-    for u in 0..n {
-        for (v, w) in graph.iter_e_from(u) {
-            heap.push((w, u, v));
-        }
+pub fn kruskal<G: Graph>(graph: &G) -> Vec<TreeEdge<G::Weight>> {
+    let edges = graph.iter_e_all().map(|(u, v, w)| (w, u, v)).collect();
+    kruskal_edges(graph.len(), edges)
+}
+
+/// return Err if the graph is empty (no spanning tree at all)
+pub fn try_kruskal<G: Graph>(graph: &G) -> Result<Vec<TreeEdge<G::Weight>>, GraphError> {
+    if graph.is_empty() {
+        Err(GraphError::EmptyGraph)
+    } else {
+        Ok(kruskal(graph))
     }
-    let mut heap = Heap::from(heap);
+}
+
+/// kruskal on an edge list (w, u, v) of a graph with `n` vertices,
+/// UGraph use it to consider every undirected edge only once
+pub fn kruskal_edges<W: Weight>(n: usize, edges: Vec<(W, usize, usize)>) -> Vec<TreeEdge<W>> {
+    let mut heap = Heap::from(edges);
 
     let mut count = 0;
     let mut ds = DisjointSet::new(n);
-    let mut res = Vec::with_capacity(n - 1);
+    let mut res = Vec::with_capacity(n.saturating_sub(1));
     while let Some((w, u, v)) = heap.pop() {
         if ds.union(u, v) {
             count += 1;
-            res.push((w, u, v));
+            res.push(TreeEdge::new(u, v, w));
             if count == n - 1 {
                 break;
             }
@@ -37,12 +46,12 @@ struct Node {
     rank: usize,
 }
 
-struct DisjointSet {
+pub(crate) struct DisjointSet {
     lst: Vec<Node>, // use compact list rather than set
 }
 
 impl DisjointSet {
-    fn new(n: usize) -> Self {
+    pub(crate) fn new(n: usize) -> Self {
         let mut lst = Vec::with_capacity(n);
         for i in 0..n {
             lst.push(Node {
@@ -54,14 +63,14 @@ impl DisjointSet {
         Self { lst }
     }
 
-    fn find_set(&mut self, i: usize) -> usize {
+    pub(crate) fn find_set(&mut self, i: usize) -> usize {
         if self.lst[i].parent != self.lst[i].id {
             self.lst[i].parent = self.find_set(self.lst[i].parent);
         }
         self.lst[i].parent
     }
 
-    fn union(&mut self, i: usize, j: usize) -> bool {
+    pub(crate) fn union(&mut self, i: usize, j: usize) -> bool {
         let i = self.find_set(i);
         let j = self.find_set(j);
         if i == j {
@@ -82,15 +91,23 @@ impl DisjointSet {
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, VGraph};
 
     #[test]
     fn test_kruskal() {
         let (g, s_lst) = MakeGraph::mst(false);
         let res = g.kruskal();
-        assert_eq!(res.iter().map(|(w, _u, _v)| *w).sum::<i32>(), 37);
-        for (w, u, v) in res.into_iter() {
-            println!("weight: {}, from: {}, to: {}", w, s_lst[u], s_lst[v]);
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37);
+        for TreeEdge { from, to, weight } in res.into_iter() {
+            println!("weight: {}, from: {}, to: {}", weight, s_lst[from.index()], s_lst[to.index()]);
         }
     }
+
+    #[test]
+    fn test_kruskal_empty() {
+        let g: VGraph<i32> = VGraph::new(vec![]);
+        assert!(kruskal(&g).is_empty());
+        assert_eq!(try_kruskal(&g), Err(GraphError::EmptyGraph));
+    }
 }
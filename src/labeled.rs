@@ -0,0 +1,264 @@
+/// graph whose vertices are Vertex<T> (label + data),
+/// so it can be built and queried by labels rather than raw indices,
+/// and algorithms' results are presented by labels too (no need to keep a symbol list)
+use crate::{bfs, dfs, dijkstra, kruskal, prim, scc, topo_sort};
+use crate::{Graph, GraphError, PathEdge, TreeEdge, VGraph, Vertex, VertexId, Weight};
+use std::collections::HashMap;
+
+pub struct LabeledGraph<T, W: Weight> {
+    vertices: Vec<Vertex<T>>,
+    index: HashMap<String, usize>, // label -> index
+    graph: VGraph<W>,
+}
+
+impl<T, W: Weight> Default for LabeledGraph<T, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, W: Weight> LabeledGraph<T, W> {
+    pub fn new() -> Self {
+        Self {
+            vertices: vec![],
+            index: HashMap::new(),
+            graph: VGraph::new(vec![]),
+        }
+    }
+
+    /// add a vertex, return its index, every label should be unique
+    pub fn add_vertex(&mut self, label: impl ToString, data: T) -> Result<usize, GraphError> {
+        let label = label.to_string();
+        if self.index.contains_key(&label) {
+            return Err(GraphError::DuplicateLabel(label));
+        }
+        let i = self.graph.add_vertex();
+        let mut vertex = Vertex::new_with_data(&label, data);
+        vertex.reset_index(i);
+        self.vertices.push(vertex);
+        self.index.insert(label, i);
+        Ok(i)
+    }
+
+    /// add edge from -> to, return the old weight if the edge already existed
+    pub fn add_edge_by_label(&mut self, from: &str, to: &str, w: W) -> Result<Option<W>, GraphError> {
+        let u = self.index_of(from)?;
+        let v = self.index_of(to)?;
+        self.graph.add_edge(u, v, w)
+    }
+
+    pub fn index_of(&self, label: &str) -> Result<usize, GraphError> {
+        match self.index.get(label) {
+            Some(&i) => Ok(i),
+            None => Err(GraphError::UnknownLabel(label.to_string())),
+        }
+    }
+
+    pub fn label(&self, u: impl Into<VertexId>) -> String {
+        self.vertices[u.into().index()].get_label()
+    }
+
+    pub fn vertex(&self, u: impl Into<VertexId>) -> &Vertex<T> {
+        &self.vertices[u.into().index()]
+    }
+
+    pub fn vertex_by_label(&self, label: &str) -> Result<&Vertex<T>, GraphError> {
+        Ok(&self.vertices[self.index_of(label)?])
+    }
+
+    /// the underlying VGraph, vertices are presented by index
+    pub fn as_vgraph(&self) -> &VGraph<W> {
+        &self.graph
+    }
+
+    fn labels(&self, lst: impl IntoIterator<Item = usize>) -> Vec<String> {
+        lst.into_iter().map(|u| self.label(u)).collect()
+    }
+
+    pub fn bfs(&self, start: &str) -> Result<Vec<String>, GraphError> {
+        Ok(self.labels(bfs::bfs(self, self.index_of(start)?)))
+    }
+
+    pub fn dfs(&self, start: &str) -> Result<Vec<String>, GraphError> {
+        Ok(self.labels(dfs::dfs(self, self.index_of(start)?)))
+    }
+
+    pub fn topo_sort_dfs(&self) -> Vec<String> {
+        self.labels(topo_sort::topo_sort_dfs(self))
+    }
+
+    pub fn scc(&self) -> Vec<Vec<String>> {
+        scc::scc(self).into_iter().map(|part| self.labels(part)).collect()
+    }
+
+    pub fn kruskal(&self) -> Vec<TreeEdge<W, String>> {
+        let res = kruskal::kruskal(self).into_iter();
+        res.map(|e| e.map_vertex(|u| self.label(u))).collect()
+    }
+
+    pub fn prim(&self) -> Vec<TreeEdge<W, String>> {
+        let res = prim::prim(self).into_iter();
+        res.map(|e| e.map_vertex(|u| self.label(u))).collect()
+    }
+
+    pub fn dijkstra(&self, start: &str) -> Result<Vec<PathEdge<W, String>>, GraphError> {
+        let res = dijkstra::dijkstra(self, self.index_of(start)?);
+        Ok(res.map(|e| e.map_vertex(|u| self.label(u))).collect())
+    }
+}
+
+impl<T, W: Weight> Graph for LabeledGraph<T, W> {
+    type Weight = W;
+    type Index = usize;
+    type IterVFrom<'a> = <VGraph<W> as Graph>::IterVFrom<'a> where Self: 'a;
+    type IterVTo<'a> = <VGraph<W> as Graph>::IterVTo<'a> where Self: 'a;
+    type IterEFrom<'a> = <VGraph<W> as Graph>::IterEFrom<'a> where Self: 'a;
+    type IterETo<'a> = <VGraph<W> as Graph>::IterETo<'a> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        self.graph.iter_v_from(u)
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_from(u)
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        self.graph.iter_v_to(u)
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        self.graph.has_fast_iter_to()
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_to(u)
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        self.graph.iter_e_from(u)
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        self.graph.iter_e_to(u)
+    }
+}
+
+/// serialized as {vertices, graph}, the label index is rebuilt (and checked) when deserializing
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, W: Weight + serde::Serialize> serde::Serialize for LabeledGraph<T, W> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("LabeledGraph", 2)?;
+        state.serialize_field("vertices", &self.vertices)?;
+        state.serialize_field("graph", &self.graph)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, W> serde::Deserialize<'de> for LabeledGraph<T, W>
+where
+    T: serde::Deserialize<'de>,
+    W: Weight + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        #[serde(bound(deserialize = "T: serde::Deserialize<'de>, W: Weight + serde::Deserialize<'de>"))]
+        struct Repr<T, W: Weight> {
+            vertices: Vec<Vertex<T>>,
+            graph: VGraph<W>,
+        }
+
+        let Repr { mut vertices, graph } = Repr::deserialize(deserializer)?;
+        if vertices.len() != graph.len() {
+            return Err(D::Error::custom(format!(
+                "{} vertices but the graph has {}",
+                vertices.len(),
+                graph.len()
+            )));
+        }
+        let mut index = HashMap::new();
+        for (i, vertex) in vertices.iter_mut().enumerate() {
+            vertex.reset_index(i);
+            if index.insert(vertex.get_label(), i).is_some() {
+                return Err(D::Error::custom(GraphError::DuplicateLabel(vertex.get_label())));
+            }
+        }
+        Ok(Self {
+            vertices,
+            index,
+            graph,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MakeGraph, NoWeight};
+
+    /// copy a testing graph into a LabeledGraph
+    fn make_labeled<W: Weight>(g: &VGraph<W>, s_lst: &[&str]) -> LabeledGraph<(), W> {
+        let mut res = LabeledGraph::new();
+        for s in s_lst.iter() {
+            res.add_vertex(s, ()).unwrap();
+        }
+        for u in 0..g.len() {
+            for (v, w) in g.iter_e_from(u) {
+                res.add_edge_by_label(s_lst[u], s_lst[v], w).unwrap();
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn test_labeled() {
+        let (g, s_lst) = MakeGraph::scc();
+        let mut lg = make_labeled(&g, &s_lst);
+        assert_eq!(lg.index_of("d"), Ok(3));
+        assert_eq!(lg.vertex(3).get_index(), 3);
+        assert_eq!(lg.label(3), "d");
+        assert_eq!(lg.add_vertex("a", ()), Err(GraphError::DuplicateLabel("a".to_string())));
+        assert_eq!(
+            lg.add_edge_by_label("a", "z", NoWeight),
+            Err(GraphError::UnknownLabel("z".to_string()))
+        );
+
+        let mut parts: Vec<Vec<String>> = lg.scc();
+        for part in parts.iter_mut() {
+            part.sort();
+        }
+        parts.sort();
+        assert_eq!(
+            parts,
+            vec![
+                vec!["a", "b", "c", "d"],
+                vec!["e", "f", "g"],
+                vec!["h", "i"],
+            ]
+        );
+        assert_eq!(lg.bfs("a").unwrap().len(), 9);
+        assert!(lg.dfs("x").is_err());
+    }
+
+    #[test]
+    fn test_labeled_algorithms() {
+        let (g, s_lst) = MakeGraph::mst(true);
+        let lg = make_labeled(&g, &s_lst);
+        assert_eq!(lg.prim().iter().map(|e| e.weight).sum::<i32>(), 37);
+        assert_eq!(lg.kruskal().iter().map(|e| e.weight).sum::<i32>(), 37);
+        for e in lg.dijkstra("h").unwrap() {
+            println!("to: {}, directly from: {}, distance: {}", e.to, e.from, e.dist);
+        }
+        let e = lg.dijkstra("a").unwrap()[0].clone();
+        assert_eq!(e.from, "a");
+        assert_eq!(Some(e.dist), g.weight(0, lg.index_of(&e.to).unwrap()));
+    }
+}
@@ -0,0 +1,111 @@
+/// lexicographic multi-criteria weight, e.g. Lex((cost, hops)): minimise cost, then hops
+/// we can not implement Add, Sub for tuples (orphan rule), so wrap them in Lex,
+/// Default/Add/Sub/infinity are component-wise, compare is lexicographic (the same as tuple)
+use crate::Weight;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lex<T>(pub T);
+
+macro_rules! impl_lex {
+    ($($t:ident $i:tt),+) => {
+        impl<$($t: Weight),+> Add for Lex<($($t,)+)> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Lex(($(self.0.$i + rhs.0.$i,)+))
+            }
+        }
+
+        impl<$($t: Weight),+> Sub for Lex<($($t,)+)> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Lex(($(self.0.$i - rhs.0.$i,)+))
+            }
+        }
+
+        impl<$($t: Weight),+> AddAssign for Lex<($($t,)+)> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.0.$i += rhs.0.$i;)+
+            }
+        }
+
+        impl<$($t: Weight),+> SubAssign for Lex<($($t,)+)> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.0.$i -= rhs.0.$i;)+
+            }
+        }
+
+        impl<$($t: Weight),+> Weight for Lex<($($t,)+)> {
+            fn infinity() -> Self {
+                Lex(($($t::infinity(),)+))
+            }
+
+            /// None if any component overflowed
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(Lex(($(self.0.$i.checked_add(rhs.0.$i)?,)+)))
+            }
+
+            /// None if any component overflowed
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Some(Lex(($(self.0.$i.checked_sub(rhs.0.$i)?,)+)))
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                Lex(($(self.0.$i.saturating_add(rhs.0.$i),)+))
+            }
+        }
+    };
+}
+
+impl_lex!(A 0, B 1);
+impl_lex!(A 0, B 1, C 2);
+impl_lex!(A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bellman_ford, dijkstra, kruskal, MakeGraph, MapWeight, PathEdge, VGraph};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_lex() {
+        // (cost, hops)
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let g2 = MapWeight::new(&g, |_u, _v, w| Lex((w, 1)));
+
+        let mut lhs: Vec<(i32, usize)> = dijkstra::dijkstra(&g, 0).map(|e| (e.dist, e.to.index())).collect();
+        let mut rhs: Vec<(i32, usize)> = dijkstra::dijkstra(&g2, 0).map(|e| (e.dist.0 .0, e.to.index())).collect();
+        lhs.sort();
+        rhs.sort();
+        assert_eq!(lhs, rhs);
+
+        let (state, dist, from) = bellman_ford::bellman_ford(&g2, 0);
+        assert!(state);
+        for (v, d) in dist.iter().enumerate() {
+            // hops is the length of the path
+            let (mut u, mut hops) = (v, 0);
+            while u != 0 {
+                u = from[u].index();
+                hops += 1;
+            }
+            assert_eq!(d.unwrap().0 .1, hops);
+        }
+
+        let res = kruskal::kruskal(&MapWeight::new(&g, |u, v, w| Lex((w, u + v, u * v))));
+        assert_eq!(res.iter().map(|e| e.weight.0 .0).sum::<i32>(), 37);
+
+        // 0 -> 1 -> 2 and 0 -> 2 cost the same, the latter has less hops
+        let g = VGraph::new(vec![
+            HashMap::from([(1, Lex((1, 1))), (2, Lex((2, 1)))]),
+            HashMap::from([(2, Lex((1, 1)))]),
+            HashMap::new(),
+        ]);
+        let res: Vec<_> = dijkstra::dijkstra(&g, 0).collect();
+        assert_eq!(res, vec![PathEdge::new(0, 1, Lex((1, 1))), PathEdge::new(0, 2, Lex((2, 1)))]);
+        assert_eq!(Lex((1u8, 250u8)).checked_add(Lex((1, 10))), None);
+        assert_eq!(Lex::<(i32, i32)>::infinity(), Lex((i32::MAX, i32::MAX)));
+    }
+}
@@ -1,16 +1,52 @@
-// pub mod vertex;
-// pub use vertex::Vertex;
+pub mod vertex;
+pub use vertex::{Vertex, VertexId};
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+pub mod error;
+pub use error::GraphError;
+
+pub mod heap;
+pub use heap::{Heap, IndexedHeap, PairingHeap};
+
+pub mod index;
+pub use index::IndexType;
 
 pub mod weight;
 pub use weight::{NoWeight, Weight};
 
+pub mod numeric;
+pub use numeric::{OrderedF64, Rational, Saturating};
+
+pub mod lex;
+pub use lex::Lex;
+
 pub mod graph;
-pub use crate::graph::{Graph, VGraph}; // ambiguously?
+pub use crate::graph::{Adjacency, EGraph, Graph, MultiGraph, SortedVGraph, VGraph}; // ambiguously?
+
+pub mod csr;
+pub use csr::CsrGraph;
+
+pub mod ugraph;
+pub use ugraph::UGraph;
+
+pub mod matrix;
+pub use matrix::MatrixGraph;
+
+pub mod labeled;
+pub use labeled::LabeledGraph;
+
+pub mod data;
+pub use data::{ByWeight, DataGraph};
+
+pub mod view;
+pub use view::{Filtered, MapWeight, Reversed};
 
 pub mod edge;
-pub use edge::{Edge, FlowEdge, CostFlowEdge}; // two traits
+pub use edge::{CostFlowEdge, Edge, EdgeId, FlowEdge}; // two traits
+
+pub mod builder;
+pub use builder::{Direction, Duplicate, GraphBuilder, SelfLoop};
 
 pub mod testing_graph;
 pub use testing_graph::MakeGraph;
@@ -32,7 +68,7 @@ pub mod kruskal; // no reverse will be faster
 pub mod prim; // need reverse
 
 pub mod dijkstra; // shortest path all need reverse edges
-pub use dijkstra::DijkstraIter;
+pub use dijkstra::{DijkstraIter, TryDijkstraIter};
 
 pub mod a_star;
 
@@ -44,6 +80,9 @@ pub mod johnson;
 
 pub mod floyd_warshall;
 
+pub mod semiring; // path algebra: widest, most reliable, counting, reachable
+pub use semiring::{OrderedSemiring, Semiring};
+
 pub mod bipartite_match; // also used is_empty_from(u)
 
 pub mod vertex_disjoint_path;
@@ -53,20 +92,27 @@ pub mod edge_disjoint_path;
 pub mod edmonds_karp;
 
 pub mod dinic_new;
-// pub mod dinic;
+
+pub mod dinic; // EGraph only
 
 pub mod mcmf;
 
-impl<W: Weight> VGraph<W> {
-    pub fn bfs(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+pub mod result; // structured results, see feature "serde"
+pub use result::{FlowAssignment, PathEdge, ShortestPathTree, TreeEdge};
+
+#[cfg(feature = "parallel")]
+pub mod parallel; // multi-threaded johnson, floyd_warshall, bfs and boruvka
+
+impl<W: Weight, Ix: IndexType, A: Adjacency<Ix, W>> VGraph<W, Ix, A> {
+    pub fn bfs(&self, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
 	bfs::bfs(self, start)
     }
 
-    pub fn dfs(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+    pub fn dfs(&self, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
 	dfs::dfs(self, start)
     }
 
-    pub fn iddfs(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+    pub fn iddfs(&self, start: impl Into<VertexId>) -> impl Iterator<Item = usize> + '_ {
 	dfs::iddfs(self, start)
     }
 
@@ -82,68 +128,219 @@ impl<W: Weight> VGraph<W> {
 	scc::scc(self)
     }
 
-    pub fn kruskal(&self) -> Vec<(W, usize, usize)> {
+    pub fn kruskal(&self) -> Vec<TreeEdge<W>> {
         kruskal::kruskal(self)
     }
 
-    pub fn prim(&self) -> Vec<(W, usize, usize)> {
+    pub fn prim(&self) -> Vec<TreeEdge<W>> {
         prim::prim(self)
     }
 
-    pub fn dijkstra(&self, start: usize) -> impl Iterator<Item = (W, usize, usize)> + '_ {
+    pub fn dijkstra(&self, start: impl Into<VertexId>) -> impl Iterator<Item = PathEdge<W>> + '_ {
         dijkstra::dijkstra(self, start)
     }
 
-    pub fn a_star<F: Fn(usize) -> W + 'static>(&self, start: usize, func: F) -> impl Iterator<Item = (W, usize, usize)> + '_ {
+    /// accumulate distances in D, see dijkstra::dijkstra_as
+    pub fn dijkstra_as<D: Weight + From<W>>(&self, start: impl Into<VertexId>) -> DijkstraIter<'_, Self, D> {
+        dijkstra::dijkstra_as(self, start)
+    }
+
+    pub fn a_star<F: Fn(usize) -> W + 'static>(&self, start: impl Into<VertexId>, func: F) -> impl Iterator<Item = PathEdge<W>> + '_ {
         a_star::a_star(self, start, func)
     }
 
-    pub fn bellman_ford(&self, start: usize) -> (bool, Vec<Option<W>>, Vec<usize>) {
+    pub fn bellman_ford(&self, start: impl Into<VertexId>) -> (bool, Vec<Option<W>>, Vec<VertexId>) {
         bellman_ford::bellman_ford(self, start)
     }
 
-    pub fn spfa(&self, start: usize) -> (bool, Vec<Option<W>>, Vec<usize>) {
+    pub fn bellman_ford_as<D: Weight + From<W>>(&self, start: impl Into<VertexId>) -> (bool, Vec<Option<D>>, Vec<VertexId>) {
+        bellman_ford::bellman_ford_as(self, start)
+    }
+
+    pub fn spfa(&self, start: impl Into<VertexId>) -> (bool, Vec<Option<W>>, Vec<VertexId>) {
 	spfa::spfa(self, start)
     }
 
-    pub fn johnson(&self) -> Vec<(Vec<Option<W>>, Vec<usize>)> {
+    pub fn spfa_as<D: Weight + From<W>>(&self, start: impl Into<VertexId>) -> (bool, Vec<Option<D>>, Vec<VertexId>) {
+	spfa::spfa_as(self, start)
+    }
+
+    pub fn johnson(&self) -> Vec<(Vec<Option<W>>, Vec<VertexId>)> {
 	johnson::johnson(self)
     }
 
+    #[allow(clippy::type_complexity)]
+    pub fn johnson_as<D: Weight + From<W>>(&self) -> Vec<(Vec<Option<D>>, Vec<VertexId>)> {
+	johnson::johnson_as(self)
+    }
+
     pub fn floyd_warshall(&self) -> Vec<Vec<Option<W>>> {
 	floyd_warshall::floyd_warshall(self)
     }
 
+    pub fn floyd_warshall_as<D: Weight + From<W>>(&self) -> Vec<Vec<Option<D>>> {
+	floyd_warshall::floyd_warshall_as(self)
+    }
+
+    pub fn widest_path(&self, start: impl Into<VertexId>) -> (Vec<W>, Vec<VertexId>) {
+	semiring::widest_path(self, start)
+    }
+
+    pub fn count_shortest_paths(&self, start: impl Into<VertexId>) -> Vec<u64> {
+	semiring::count_shortest_paths(self, start)
+    }
+
+    // following methods return Err rather than panic
+
+    pub fn try_kruskal(&self) -> Result<Vec<TreeEdge<W>>, GraphError> {
+        kruskal::try_kruskal(self)
+    }
+
+    pub fn try_prim(&self) -> Result<Vec<TreeEdge<W>>, GraphError> {
+        prim::try_prim(self)
+    }
+
+    pub fn try_dijkstra(&self, start: impl Into<VertexId>) -> Result<impl Iterator<Item = Result<PathEdge<W>, GraphError>> + '_, GraphError> {
+        dijkstra::try_dijkstra(self, start)
+    }
+
+    pub fn try_bellman_ford(&self, start: impl Into<VertexId>) -> Result<(Vec<Option<W>>, Vec<VertexId>), GraphError> {
+        bellman_ford::try_bellman_ford(self, start)
+    }
+
+    pub fn try_spfa(&self, start: impl Into<VertexId>) -> Result<(Vec<Option<W>>, Vec<VertexId>), GraphError> {
+	spfa::try_spfa(self, start)
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn try_johnson(&self) -> Result<Vec<(Vec<Option<W>>, Vec<VertexId>)>, GraphError> {
+	johnson::try_johnson(self)
+    }
+
+    pub fn try_floyd_warshall(&self) -> Result<Vec<Vec<Option<W>>>, GraphError> {
+	floyd_warshall::try_floyd_warshall(self)
+    }
+}
+
+// matchings, disjoint paths and flows are kept in the same kind of map as the adjacency
+
+impl<W: Weight, Ix: IndexType> VGraph<W, Ix> {
     pub fn bipartite_match(&self) -> HashMap<usize, usize> {
 	bipartite_match::bipartite_match(self)
     }
 
-    pub fn vertex_disjoint_path(&self, start: usize, target: usize) -> (HashMap<usize, usize>, Vec<usize>) {
+    pub fn vertex_disjoint_path(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> (HashMap<usize, usize>, Vec<usize>) {
 	vertex_disjoint_path::vertex_disjoint_path(self, start, target)
     }
 
-    pub fn edge_disjoint_path(&self, start: usize, target: usize) -> HashMap<usize, HashSet<usize>> {
+    pub fn edge_disjoint_path(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> HashMap<usize, HashSet<usize>> {
 	edge_disjoint_path::edge_disjoint_path(self, start, target)
     }
 
-    pub fn edmonds_karp(&self, start: usize, target: usize) -> HashMap<usize, HashMap<usize, W>> {
+    pub fn edmonds_karp(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> HashMap<usize, HashMap<usize, W>> {
 	edmonds_karp::edmonds_karp(self, start, target)
     }
+}
+
+/// the same results in BTreeMap/BTreeSet, so they are printed (or serialized) the same from run to run
+impl<W: Weight> SortedVGraph<W> {
+    pub fn bipartite_match(&self) -> BTreeMap<usize, usize> {
+	bipartite_match::bipartite_match(self).into_iter().collect()
+    }
+
+    pub fn vertex_disjoint_path(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> (BTreeMap<usize, usize>, Vec<usize>) {
+	let (matching, last_but_t) = vertex_disjoint_path::vertex_disjoint_path(self, start, target);
+	(matching.into_iter().collect(), last_but_t)
+    }
+
+    pub fn edge_disjoint_path(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> BTreeMap<usize, BTreeSet<usize>> {
+	let matching = edge_disjoint_path::edge_disjoint_path(self, start, target);
+	matching.into_iter().map(|(v, out)| (v, out.into_iter().collect())).collect()
+    }
+
+    pub fn edmonds_karp(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> BTreeMap<usize, BTreeMap<usize, W>> {
+	let flowing = edmonds_karp::edmonds_karp(self, start, target);
+	flowing.into_iter().map(|(v, dct)| (v, dct.into_iter().collect())).collect()
+    }
+
+    pub fn dinic(&self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> BTreeMap<usize, BTreeMap<usize, W>> {
+	let flowing = dinic_new::dinic(self, start, target);
+	flowing.into_iter().map(|(v, dct)| (v, dct.into_iter().collect())).collect()
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<W: Weight + Send + Sync, Ix: IndexType, A: Adjacency<Ix, W> + Sync> VGraph<W, Ix, A> {
+    pub fn par_bfs(&self, start: impl Into<VertexId>) -> Vec<usize> {
+	parallel::par_bfs(self, start)
+    }
+
+    pub fn par_johnson(&self) -> Vec<(Vec<Option<W>>, Vec<VertexId>)> {
+	parallel::par_johnson(self)
+    }
+
+    pub fn par_floyd_warshall(&self) -> Vec<Vec<Option<W>>> {
+	parallel::par_floyd_warshall(self)
+    }
+
+    pub fn par_boruvka(&self) -> Vec<TreeEdge<W>> {
+	parallel::par_boruvka(self)
+    }
+}
+
+impl<W: Weight> UGraph<W> {
+    /// every undirected edge is only considered once
+    pub fn kruskal(&self) -> Vec<TreeEdge<W>> {
+        let edges = self.iter_undirected_edges().map(|(u, v, w)| (w, u, v)).collect();
+        kruskal::kruskal_edges(self.len(), edges)
+    }
 
+    pub fn prim(&self) -> Vec<TreeEdge<W>> {
+        prim::prim(self)
+    }
+
+    pub fn dijkstra(&self, start: impl Into<VertexId>) -> impl Iterator<Item = PathEdge<W>> + '_ {
+        dijkstra::dijkstra(self, start)
+    }
+}
+
+impl<W: Weight> MatrixGraph<W> {
+    pub fn floyd_warshall(&self) -> Vec<Vec<Option<W>>> {
+	floyd_warshall::floyd_warshall_matrix(self)
+    }
 }
 
+impl<W, E> EGraph<W, E>
+where
+    W: Weight,
+    E: Edge<W>,
+{
+    pub fn kruskal(&self) -> Vec<TreeEdge<W>> {
+        kruskal::kruskal(self)
+    }
 
-// impl<W, E> EGraph<W, E>
-// where
-//     W: Weight,
-//     E: WeightedEdge<W>
-// {
-//     pub fn kruskal(&self) -> Vec<(W, usize, usize)> {
-//         kruskal::kruskal(self)
-//     }
-
-//     // TODO: add other impls
-//     pub fn edmonds_karp(&self, start: usize, target: usize) -> HashMap<usize, HashMap<usize, W>> {
-// 	edmonds_karp::edmonds_karp(self, start, target)
-//     }
-// }
+    pub fn dijkstra_tree(&self, start: impl Into<VertexId>) -> Vec<Option<(W, Option<EdgeId>)>> {
+	dijkstra::dijkstra_tree(self, start)
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn try_dijkstra_tree(&self, start: impl Into<VertexId>) -> Result<Vec<Option<(W, Option<EdgeId>)>>, GraphError> {
+	dijkstra::try_dijkstra_tree(self, start)
+    }
+}
+
+impl<C: Weight, W: Weight> EGraph<C, CostFlowEdge<C, W>> {
+    pub fn mcmf(&mut self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> W {
+	mcmf::mcmf_egraph(self, start, target)
+    }
+}
+
+impl<W: Weight> EGraph<W, FlowEdge<W>> {
+    pub fn dinic(&mut self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> W {
+	dinic::dinic(self, start, target)
+    }
+
+    pub fn edmonds_karp(&mut self, start: impl Into<VertexId>, target: impl Into<VertexId>) -> W {
+	edmonds_karp::edmonds_karp_egraph(self, start, target)
+    }
+}
@@ -0,0 +1,194 @@
+/// dense graph, all edges are kept in a flat n x n matrix:
+/// data[u * n + v] is the weight of edge u -> v (None if no such edge)
+/// has_edge/weight are O(1), iter_*_from/iter_*_to are O(V)
+/// it's good for small dense graph, which HashMap costs too much
+use crate::error::check_vertex;
+use crate::{Graph, GraphError, VGraph, VertexId, Weight};
+use crate::graph::Vertices;
+use std::collections::HashMap;
+use std::iter::{Enumerate, StepBy};
+use std::slice::Iter;
+
+#[derive(Clone)]
+pub struct MatrixGraph<W: Weight> {
+    n: usize,
+    data: Vec<Option<W>>,
+}
+
+impl<W: Weight> MatrixGraph<W> {
+    /// make a graph of `n` isolated vertices
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            data: vec![None; n * n],
+        }
+    }
+
+    /// add edge u -> v, return the old weight if the edge already existed
+    pub fn add_edge(&mut self, u: usize, v: usize, w: W) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.data[u * self.n + v].replace(w))
+    }
+
+    /// remove edge u -> v, return its weight if the edge existed
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.data[u * self.n + v].take())
+    }
+
+    /// Graph::has_edge, but an out of range u or v is an error rather than a panic
+    pub fn try_has_edge(&self, u: usize, v: usize) -> Result<bool, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.data[u * self.n + v].is_some())
+    }
+
+    /// Graph::weight, but an out of range u or v is an error rather than a panic
+    pub fn try_weight(&self, u: usize, v: usize) -> Result<Option<W>, GraphError> {
+        self.check_edge(u, v)?;
+        Ok(self.data[u * self.n + v])
+    }
+
+    /// the uth row of the matrix: weights of all edges from `u`
+    pub fn row(&self, u: usize) -> &[Option<W>] {
+        &self.data[u * self.n..(u + 1) * self.n]
+    }
+
+    fn check_edge(&self, u: usize, v: usize) -> Result<(), GraphError> {
+        check_vertex(u, self.n)?;
+        check_vertex(v, self.n)
+    }
+}
+
+impl<W: Weight> From<&VGraph<W>> for MatrixGraph<W> {
+    fn from(graph: &VGraph<W>) -> Self {
+        let n = graph.len();
+        let mut res = Self::new(n);
+        for (u, v, w) in graph.iter_e_all() {
+            res.data[u * n + v] = Some(w);
+        }
+        res
+    }
+}
+
+impl<W: Weight> From<&MatrixGraph<W>> for VGraph<W> {
+    fn from(graph: &MatrixGraph<W>) -> Self {
+        let lst = (0..graph.n)
+            .map(|u| graph.iter_e_from(u).collect::<HashMap<usize, W>>())
+            .collect();
+        VGraph::new(lst)
+    }
+}
+
+/// the existing edges of a row (or a column, with step n)
+pub struct MatrixIter<'a, W>(Enumerate<StepBy<Iter<'a, Option<W>>>>);
+
+impl<'a, W: Copy> Iterator for MatrixIter<'a, W> {
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        self.0.by_ref().find_map(|(v, w)| w.map(|w| (v, w)))
+    }
+}
+
+impl<W: Weight> Graph for MatrixGraph<W> {
+    type Weight = W;
+    type Index = usize;
+    type IterVFrom<'a> = Vertices<MatrixIter<'a, W>> where Self: 'a;
+    type IterVTo<'a> = Vertices<MatrixIter<'a, W>> where Self: 'a;
+    type IterEFrom<'a> = MatrixIter<'a, W> where Self: 'a;
+    type IterETo<'a> = MatrixIter<'a, W> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.n
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        MatrixIter(self.row(u).iter().step_by(1).enumerate())
+    }
+
+    /// scan the uth column
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        MatrixIter(self.data[u..].iter().step_by(self.n).enumerate())
+    }
+
+    fn edge_count(&self) -> usize {
+        self.data.iter().filter(|w| w.is_some()).count()
+    }
+
+    fn has_edge(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.data[u * self.n + v].is_some()
+    }
+
+    fn weight(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> Option<W> {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.data[u * self.n + v]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MakeGraph;
+
+    #[test]
+    fn test_matrix() {
+        let (g, _s_lst) = MakeGraph::mf();
+        let m = MatrixGraph::from(&g);
+        for u in 0..g.len() {
+            for v in 0..g.len() {
+                assert_eq!(m.weight(u, v), g.weight(u, v));
+            }
+            let mut lhs: Vec<(usize, i32)> = g.iter_e_to(u).collect();
+            lhs.sort();
+            assert_eq!(lhs, m.iter_e_to(u).collect::<Vec<_>>());
+        }
+
+        let g2 = VGraph::from(&m);
+        for u in 0..g.len() {
+            let mut lhs: Vec<(usize, i32)> = g.iter_e_from(u).collect();
+            let mut rhs: Vec<(usize, i32)> = g2.iter_e_from(u).collect();
+            lhs.sort();
+            rhs.sort();
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn test_matrix_mutation() {
+        let mut m = MatrixGraph::new(2);
+        assert_eq!(m.add_edge(0, 1, 3), Ok(None));
+        assert_eq!(m.try_has_edge(0, 1), Ok(true));
+        assert_eq!(m.try_has_edge(1, 0), Ok(false));
+        assert_eq!(m.remove_edge(0, 1), Ok(Some(3)));
+        assert_eq!(
+            m.add_edge(2, 0, 1),
+            Err(GraphError::VertexOutOfRange { index: 2, len: 2 })
+        );
+    }
+
+    #[test]
+    fn test_matrix_floyd_warshall() {
+        let (g, _s_lst) = MakeGraph::spn();
+        let m = MatrixGraph::from(&g);
+        assert_eq!(m.floyd_warshall(), g.floyd_warshall());
+    }
+}
@@ -1,7 +1,7 @@
 /// Minimum Cost Maximum Flow
-use crate::{Graph, Weight};
-/// use std::collections::VecDeque;
-use std::collections::HashMap;
+use crate::graph::Vertices;
+use crate::{CostFlowEdge, EGraph, EdgeId, Graph, GraphError, VertexId, Weight};
+use std::collections::{HashMap, VecDeque};
 
 type Flowing<W> = Vec<HashMap<usize, (W, W)>>;
 
@@ -10,7 +10,7 @@ type Flowing<W> = Vec<HashMap<usize, (W, W)>>;
 /// we find shortest path on residual cost networks,
 /// using something like augmenting path,
 /// we can get the fact: we just find the minimum cost paths on original network
-
+///
 /// proof
 /// each step: we have original shortest path a, and next shortest path b,
 /// if not greedy we can found c and d, so that c + d < a + b
@@ -19,33 +19,113 @@ type Flowing<W> = Vec<HashMap<usize, (W, W)>>;
 /// else we can break and remake c + d to a + e,
 /// since b is the shortest we have e >= b, so c + d >= a + b
 /// NOTE: cost are always positive, but we can have negative cost on residual networks
+/// NOTE: panic if start/target is out of range or some edge's capacity is not in cap_dct,
+/// use try_mcmf to avoid it
 pub fn mcmf<G: Graph>(
     graph: &G, // cost graph
     cap_dct: &HashMap<(usize, usize), G::Weight>,
-    start: usize,
-    target: usize,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
 ) -> (bool, Flowing<G::Weight>) {
+    let (start, target) = check_ends(graph, start, target);
+    try_mcmf(graph, cap_dct, start, target).expect("Capacity not found!")
+}
+
+/// return Err if start/target is out of range, or some edge's capacity is not in cap_dct
+pub fn try_mcmf<G: Graph>(
+    graph: &G, // cost graph
+    cap_dct: &HashMap<(usize, usize), G::Weight>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> Result<(bool, Flowing<G::Weight>), GraphError> {
+    solve(graph, |u, v| cap_dct.get(&(u, v)).copied(), start, target)
+}
+
+/// the same as mcmf, but capacities are the weights of `cap_graph`, which has the same edges as `graph`
+/// NOTE: panic if start/target is out of range or some edge is not in cap_graph,
+/// use try_mcmf_cap_graph to avoid it
+pub fn mcmf_cap_graph<G, C>(
+    graph: &G, // cost graph
+    cap_graph: &C,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> (bool, Flowing<G::Weight>)
+where
+    G: Graph,
+    C: Graph<Weight = G::Weight>,
+{
+    let (start, target) = check_ends(graph, start, target);
+    try_mcmf_cap_graph(graph, cap_graph, start, target).expect("Capacity not found!")
+}
+
+/// panic with VertexOutOfRange (rather than a missing capacity) if start/target is out of range
+fn check_ends<G: Graph>(
+    graph: &G,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> (usize, usize) {
+    let start = start.into().index();
+    let target = target.into().index();
+    if let Err(e) = graph.check_vertex(start).and(graph.check_vertex(target)) {
+        panic!("{}", e);
+    }
+    (start, target)
+}
+
+/// return Err if start/target is out of range, or some edge is not in cap_graph
+pub fn try_mcmf_cap_graph<G, C>(
+    graph: &G, // cost graph
+    cap_graph: &C,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> Result<(bool, Flowing<G::Weight>), GraphError>
+where
+    G: Graph,
+    C: Graph<Weight = G::Weight>,
+{
+    let n = cap_graph.len();
+    let cap = |u: usize, v: usize| {
+        if u < n && v < n {
+            cap_graph.weight(u, v)
+        } else {
+            None
+        }
+    };
+    solve(graph, cap, start, target)
+}
+
+/// `cap(u, v)` is the capacity of edge u -> v of the cost graph
+fn solve<G: Graph>(
+    graph: &G,
+    cap: impl Fn(usize, usize) -> Option<G::Weight>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> Result<(bool, Flowing<G::Weight>), GraphError> {
+    let start = start.into().index();
+    let target = target.into().index();
+    graph.check_vertex(start)?;
+    graph.check_vertex(target)?;
     let zero = Default::default();
 
-    let mut rgraph = Residual::new(graph, cap_dct);
+    let mut rgraph = Residual::new(graph, cap)?;
     loop {
         let (_state, dist, from) = rgraph.spfa(start);
         match dist[target] {
             Some(d) => {
                 if d < zero {
-                    return (false, rgraph.flowing);
+                    return Ok((false, rgraph.flowing));
                 }
                 let mut v = target;
-                let mut flow = rgraph.get_residual_forward_flow(from[v], v);
+                let mut flow = rgraph.get_residual_flow(from[v].index(), v);
                 while v != start {
-                    flow = flow.min(rgraph.get_residual_flow(from[v], v));
-                    v = from[v];
+                    flow = flow.min(rgraph.get_residual_flow(from[v].index(), v));
+                    v = from[v].index();
                 }
 
                 let mut v = target;
                 while v != start {
-                    let u = from[v];
-                    if cap_dct.contains_key(&(u, v)) {
+                    let u = from[v].index();
+                    if rgraph.flowing[v].contains_key(&u) {
                         rgraph.add_forward_flow(u, v, flow);
                     } else {
                         rgraph.add_backward_flow(u, v, flow);
@@ -53,9 +133,127 @@ pub fn mcmf<G: Graph>(
                     v = u;
                 }
             }
-            None => return (true, rgraph.flowing),
+            None => return Ok((true, rgraph.flowing)),
+        }
+    }
+}
+
+/// the same algorithm as mcmf, but run on EGraph (parallel edges are ok),
+/// every edge carries its own cost and capacity, the flow of every edge
+/// is written back to the graph (see EGraph::iter_flow), return the max flow
+/// NOTE: cost should be non-negative, then no negative cycle on residual networks,
+/// panic if start/target is out of range or a negative cycle is found, use try_mcmf_egraph to avoid it
+pub fn mcmf_egraph<C: Weight, W: Weight>(
+    graph: &mut EGraph<C, CostFlowEdge<C, W>>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> W {
+    try_mcmf_egraph(graph, start, target).unwrap()
+}
+
+/// return Err if start/target is out of range, or a negative cycle is found on residual networks
+/// (then the flow written back so far is not the max flow)
+pub fn try_mcmf_egraph<C: Weight, W: Weight>(
+    graph: &mut EGraph<C, CostFlowEdge<C, W>>,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
+) -> Result<W, GraphError> {
+    let start = start.into().index();
+    let target = target.into().index();
+    graph.check_vertex(start)?;
+    graph.check_vertex(target)?;
+    let mut total = Default::default();
+    if start == target {
+        return Ok(total);
+    }
+    while let Some(path) = find_cheapest_path(graph, start, target)? {
+        // path is backward: from target to start
+        let (i, forward) = path[0];
+        let mut flow = get_residual(graph, i, forward);
+        for &(i, forward) in path.iter() {
+            flow = flow.min(get_residual(graph, i, forward));
+        }
+        for &(i, forward) in path.iter() {
+            let e = graph.get_edge_mut(i);
+            if forward {
+                e.flow += flow;
+            } else {
+                e.flow -= flow;
+            }
+        }
+        total += flow;
+    }
+    Ok(total)
+}
+
+fn get_residual<C: Weight, W: Weight>(
+    graph: &EGraph<C, CostFlowEdge<C, W>>,
+    i: EdgeId,
+    forward: bool,
+) -> W {
+    let e = graph.get_edge(i);
+    if forward {
+        e.cap - e.flow
+    } else {
+        e.flow
+    }
+}
+
+/// spfa on residual networks, return the edges (and whether it is forward) of
+/// the cheapest augmenting path (from target to start), None if no path found
+fn find_cheapest_path<C: Weight, W: Weight>(
+    graph: &EGraph<C, CostFlowEdge<C, W>>,
+    start: usize,
+    target: usize,
+) -> Result<Option<Vec<(EdgeId, bool)>>, GraphError> {
+    let n = graph.len();
+    let mut dist: Vec<Option<C>> = vec![None; n];
+    let mut from: Vec<Option<(EdgeId, bool)>> = vec![None; n];
+    let mut count = vec![0; n];
+    let mut is_in_queue = vec![false; n];
+    let mut queue = VecDeque::new();
+    dist[start] = Some(Default::default());
+    queue.push_back(start);
+
+    while let Some(u) = queue.pop_front() {
+        is_in_queue[u] = false;
+        let du = dist[u].unwrap();
+        for i in graph.iter_e_around(u) {
+            let e = graph.get_edge(i);
+            let (v, cost, forward) = if e.from == u && e.cap > e.flow {
+                (e.to, e.cost, true)
+            } else if e.to == u && e.flow.is_positive() {
+                (e.from, C::zero() - e.cost, false)
+            } else {
+                continue;
+            };
+            if dist[v].is_none() || du + cost < dist[v].unwrap() {
+                dist[v] = Some(du + cost);
+                from[v] = Some((i, forward));
+                if !is_in_queue[v] {
+                    count[v] += 1;
+                    if count[v] > n {
+                        return Err(GraphError::NegativeCycle);
+                    }
+                    queue.push_back(v);
+                    is_in_queue[v] = true;
+                }
+            }
         }
     }
+
+    if dist[target].is_none() {
+        return Ok(None);
+    }
+    let mut path = vec![];
+    let mut v = target;
+    while v != start {
+        let (i, forward) = from[v].unwrap();
+        let e = graph.get_edge(i);
+        v = if forward { e.from } else { e.to };
+        path.push((i, forward));
+    }
+    Ok(Some(path))
 }
 
 struct Residual<'a, G: Graph> {
@@ -66,17 +264,20 @@ struct Residual<'a, G: Graph> {
 impl<'a, G: Graph> Residual<'a, G> {
     // NOTE: make flowing carefully
 
-    fn new(graph: &'a G, cap_dct: &'a HashMap<(usize, usize), G::Weight>) -> Self {
+    fn new(
+        graph: &'a G,
+        cap: impl Fn(usize, usize) -> Option<G::Weight>,
+    ) -> Result<Self, GraphError> {
         let n = graph.len();
         let mut flowing = vec![HashMap::new(); n];
         let zero = Default::default();
         for u in 0..n {
             for v in graph.iter_v_from(u) {
-                let w = *cap_dct.get(&(u, v)).unwrap();
+                let w = cap(u, v).ok_or(GraphError::MissingCapacity { from: u, to: v })?;
                 flowing[v].insert(u, (w, zero));
             }
         }
-        Self { graph, flowing }
+        Ok(Self { graph, flowing })
     }
 
     fn get_residual_forward_flow(&self, u: usize, v: usize) -> G::Weight {
@@ -117,52 +318,103 @@ impl<'a, G: Graph> Residual<'a, G> {
         *p = (p.0, p.1 - w);
     }
 
-    fn spfa(&self, start: usize) -> (bool, Vec<Option<G::Weight>>, Vec<usize>) {
+    fn spfa(&self, start: usize) -> (bool, Vec<Option<G::Weight>>, Vec<VertexId>) {
         crate::spfa::spfa(self, start)
     }
 }
 
 impl<'a, G: Graph> Graph for Residual<'a, G> {
     type Weight = G::Weight;
+    type Index = G::Index;
+    type IterVFrom<'b> = Vertices<Self::IterEFrom<'b>> where Self: 'b;
+    type IterVTo<'b> = Vertices<Self::IterETo<'b>> where Self: 'b;
+    type IterEFrom<'b> = ResidualIter<'b, 'a, G, G::IterEFrom<'b>, G::IterETo<'b>> where Self: 'b;
+    type IterETo<'b> = ResidualIter<'b, 'a, G, G::IterETo<'b>, G::IterEFrom<'b>> where Self: 'b;
 
     fn len(&self) -> usize {
         self.graph.len()
     }
 
-    fn iter_v_from(&self, _u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-        unimplemented!();
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
     }
 
-    fn iter_v_to(&self, _u: usize) -> Box<dyn Iterator<Item = usize> + '_> {
-        unimplemented!();
+    fn has_fast_iter_to(&self) -> bool {
+        self.graph.has_fast_iter_to()
     }
 
     /// iter all the edges from vertex `u`
-    fn iter_e_from(&self, u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_> {
-        // TODO:
-        let zero: Self::Weight = Default::default();
-        let iter_forward = self
-            .graph
-            .iter_e_from(u)
-            .filter(move |e| self.can_add_forward_flow(u, e.0));
-        let iter_backward = self
-            .graph
-            .iter_e_to(u)
-            .filter(move |e| self.can_add_backward_flow(u, e.0))
-            .map(move |(v, w)| (v, zero - w));
-        Box::new(iter_forward.chain(iter_backward))
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        ResidualIter {
+            rgraph: self,
+            u,
+            same: self.graph.iter_e_from(u),
+            opposite: self.graph.iter_e_to(u),
+            to: false,
+        }
     }
 
     /// iter all the edges to vertex `u`
-    fn iter_e_to(&self, _u: usize) -> Box<dyn Iterator<Item = (usize, Self::Weight)> + '_> {
-        unimplemented!();
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        ResidualIter {
+            rgraph: self,
+            u,
+            same: self.graph.iter_e_to(u),
+            opposite: self.graph.iter_e_from(u),
+            to: true,
+        }
+    }
+}
+
+/// the edges of vertex `u` on residual networks:
+/// `same`: the edges of the original graph which still can add flow, with the cost,
+/// `opposite`: the reversed edges which have flow, with the negative cost
+struct ResidualIter<'b, 'a, G: Graph, I, J> {
+    rgraph: &'b Residual<'a, G>,
+    u: usize,
+    same: I,
+    opposite: J,
+    to: bool, // the edges to `u`, else from `u`
+}
+
+impl<'b, 'a, G, I, J> Iterator for ResidualIter<'b, 'a, G, I, J>
+where
+    G: Graph,
+    I: Iterator<Item = (usize, G::Weight)>,
+    J: Iterator<Item = (usize, G::Weight)>,
+{
+    type Item = (usize, G::Weight);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.u;
+        for (v, w) in self.same.by_ref() {
+            let (x, y) = if self.to { (v, u) } else { (u, v) };
+            if self.rgraph.can_add_forward_flow(x, y) {
+                return Some((v, w));
+            }
+        }
+        for (v, w) in self.opposite.by_ref() {
+            let (x, y) = if self.to { (v, u) } else { (u, v) };
+            if self.rgraph.can_add_backward_flow(x, y) {
+                return Some((v, G::Weight::zero() - w));
+            }
+        }
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::MakeGraph;
+    use crate::{GraphBuilder, MakeGraph};
 
     #[test]
     fn test_mcmf() {
@@ -182,4 +434,78 @@ mod tests {
             println!("}}");
         }
     }
+
+    #[test]
+    fn test_try_mcmf() {
+        let (g, _s_lst, mut cap_dct) = MakeGraph::mcmf();
+        assert!(try_mcmf(&g, &cap_dct, 0, 5).is_ok());
+        cap_dct.remove(&(0, 1));
+        assert_eq!(
+            try_mcmf(&g, &cap_dct, 0, 5),
+            Err(GraphError::MissingCapacity { from: 0, to: 1 })
+        );
+        assert!(try_mcmf(&g, &cap_dct, 0, 6).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex 6 out of range")]
+    fn test_mcmf_out_of_range() {
+        let (g, _s_lst, cap_dct) = MakeGraph::mcmf();
+        mcmf(&g, &cap_dct, 0, 6);
+    }
+
+    #[test]
+    fn test_mcmf_cap_graph() {
+        let (g, _s_lst, mut cap_dct) = MakeGraph::mcmf();
+        let edges = cap_dct.iter().map(|(&(u, v), &w)| (u, v, w));
+        let cap_graph = GraphBuilder::new(g.len()).edges(edges).build_vgraph().unwrap();
+        assert_eq!(mcmf_cap_graph(&g, &cap_graph, 0, 5), mcmf(&g, &cap_dct, 0, 5));
+
+        cap_dct.remove(&(0, 1));
+        let edges = cap_dct.iter().map(|(&(u, v), &w)| (u, v, w));
+        let cap_graph = GraphBuilder::new(g.len()).edges(edges).build_vgraph().unwrap();
+        assert_eq!(
+            try_mcmf_cap_graph(&g, &cap_graph, 0, 5),
+            Err(GraphError::MissingCapacity { from: 0, to: 1 })
+        );
+        let small = GraphBuilder::<i32>::new(2).build_vgraph().unwrap();
+        assert!(try_mcmf_cap_graph(&g, &small, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_residual() {
+        let (g, _s_lst, cap_dct) = MakeGraph::mcmf();
+        let mut rgraph = Residual::new(&g, |u, v| cap_dct.get(&(u, v)).copied()).unwrap();
+        rgraph.add_forward_flow(0, 1, 16); // s -> v1 is full
+        rgraph.add_forward_flow(1, 3, 5);
+        assert_eq!(rgraph.iter_e_from(0).collect::<Vec<_>>(), vec![(2, 1)]);
+        let mut lhs: Vec<_> = rgraph.iter_e_from(1).collect();
+        lhs.sort();
+        assert_eq!(lhs, vec![(0, -3), (3, 1)]);
+        for u in 0..rgraph.len() {
+            for (v, w) in rgraph.iter_e_from(u) {
+                assert!(rgraph.iter_e_to(v).any(|e| e == (u, w)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mcmf_egraph() {
+        let mut g = EGraph::new(vec![], 3);
+        let a = g.add_edge(CostFlowEdge::new(0, 1, 1, 2)).unwrap();
+        let b = g.add_edge(CostFlowEdge::new(0, 1, 5, 10)).unwrap();
+        let c = g.add_edge(CostFlowEdge::new(1, 2, 1, 3)).unwrap();
+        assert_eq!(mcmf_egraph(&mut g, 0, 2), 3);
+        assert_eq!(g.get_flow(a), 2); // the cheaper parallel edge is full
+        assert_eq!(g.get_flow(b), 1);
+        assert_eq!(g.get_flow(c), 3);
+
+        assert_eq!(mcmf_egraph(&mut g, 0, 0), 0);
+        assert!(try_mcmf_egraph(&mut g, 0, 3).is_err());
+        let mut g = EGraph::new(vec![], 3);
+        g.add_edge(CostFlowEdge::new(0, 1, 1, 1)).unwrap();
+        g.add_edge(CostFlowEdge::new(1, 2, -3, 1)).unwrap();
+        g.add_edge(CostFlowEdge::new(2, 1, 1, 1)).unwrap();
+        assert_eq!(try_mcmf_egraph(&mut g, 0, 2), Err(GraphError::NegativeCycle));
+    }
 }
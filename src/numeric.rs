@@ -0,0 +1,485 @@
+/// crate-owned numeric weights, which are totally ordered (no NaN can get in):
+/// OrderedF64: f64 without NaN
+/// Rational: exact i64 / i64, no rounding error (e.g. for flow problems)
+/// Saturating: integer whose MAX/MIN are +/- infinity, never overflow
+use crate::{GraphError, Weight};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// NOTE: panic if an operation produce NaN (e.g. inf - inf)
+#[derive(Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f64", into = "f64"))]
+pub struct OrderedF64(f64);
+
+impl OrderedF64 {
+    /// return Err if `x` is NaN
+    pub fn new(x: f64) -> Result<Self, GraphError> {
+        if x.is_nan() {
+            Err(GraphError::InvalidWeight(format!("{}", x)))
+        } else {
+            Ok(Self(x))
+        }
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+
+    fn checked(x: f64) -> Self {
+        Self::new(x).expect("OrderedF64: NaN produced")
+    }
+}
+
+impl TryFrom<f64> for OrderedF64 {
+    type Error = GraphError;
+
+    fn try_from(x: f64) -> Result<Self, GraphError> {
+        Self::new(x)
+    }
+}
+
+impl From<OrderedF64> for f64 {
+    fn from(x: OrderedF64) -> f64 {
+        x.0
+    }
+}
+
+impl Eq for OrderedF64 {}
+
+impl Ord for OrderedF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap()
+    }
+}
+
+impl PartialOrd for OrderedF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for OrderedF64 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::checked(self.0 + rhs.0)
+    }
+}
+
+impl Sub for OrderedF64 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::checked(self.0 - rhs.0)
+    }
+}
+
+impl AddAssign for OrderedF64 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for OrderedF64 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl fmt::Debug for OrderedF64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl fmt::Display for OrderedF64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Weight for OrderedF64 {
+    fn infinity() -> Self {
+        Self(f64::INFINITY)
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).and_then(|x| Self::new(x).ok())
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).and_then(|x| Self::new(x).ok())
+    }
+}
+
+/// always kept in lowest terms with a positive denominator,
+/// so the derived PartialEq/Hash are right
+/// infinity is i64::MAX / 1 (-infinity is -i64::MAX / 1), both of them absorb any finite number,
+/// arithmetic is done in i128
+/// NOTE: panic if the result can not be held by i64 / i64 (use checked_add to avoid it)
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i64,
+    den: i64,
+}
+
+impl Rational {
+    /// return Err if `den` is zero, or the reduced value is i64::MAX / 1 or -i64::MAX / 1
+    /// (they are kept for +/- infinity) or i64::MIN / 1 (it's less than -infinity)
+    pub fn new(num: i64, den: i64) -> Result<Self, GraphError> {
+        let err = || GraphError::InvalidWeight(format!("{}/{}", num, den));
+        if den == 0 {
+            return Err(err());
+        }
+        let res = Self::reduce(num as i128, den as i128).ok_or_else(err)?;
+        if res.is_infinite() || (res.den == 1 && res.num == i64::MIN) {
+            Err(err())
+        } else {
+            Ok(res)
+        }
+    }
+
+    /// the same as new(n, 1)
+    pub fn from_integer(n: i64) -> Result<Self, GraphError> {
+        Self::new(n, 1)
+    }
+
+    pub fn numer(&self) -> i64 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i64 {
+        self.den
+    }
+
+    /// den != 0, None if overflowed
+    fn reduce(mut num: i128, mut den: i128) -> Option<Self> {
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+        let g = gcd(num.abs(), den);
+        Some(Self {
+            num: i64::try_from(num / g).ok()?,
+            den: i64::try_from(den / g).ok()?,
+        })
+    }
+
+    fn is_infinite(&self) -> bool {
+        self.den == 1 && (self.num == i64::MAX || self.num == -i64::MAX)
+    }
+
+    fn checked_add_impl(self, rhs: Self) -> Option<Self> {
+        if self.is_infinite() {
+            return Some(self);
+        } else if rhs.is_infinite() {
+            return Some(rhs);
+        }
+        let (a, b) = (self.num as i128, self.den as i128);
+        let (c, d) = (rhs.num as i128, rhs.den as i128);
+        Self::reduce(a * d + c * b, b * d)
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self { num: 0, den: 1 }
+    }
+}
+
+impl TryFrom<i64> for Rational {
+    type Error = GraphError;
+
+    fn try_from(n: i64) -> Result<Self, GraphError> {
+        Self::from_integer(n)
+    }
+}
+
+/// (num, den), checked and reduced when deserializing
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rational {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&(self.num, self.den), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rational {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (num, den) = <(i64, i64)>::deserialize(deserializer)?;
+        Self::new(num, den).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num as i128 * other.den as i128).cmp(&(other.num as i128 * self.den as i128))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::reduce(-(self.num as i128), self.den as i128).expect("Rational overflowed")
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add_impl(rhs).expect("Rational overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl fmt::Debug for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Weight for Rational {
+    fn infinity() -> Self {
+        Self { num: i64::MAX, den: 1 }
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.checked_add_impl(rhs)
+    }
+
+    /// -rhs never overflows, since i64::MIN is not a valid numerator
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add_impl(-rhs)
+    }
+}
+
+/// MAX is +infinity, MIN is -infinity, both of them absorb any finite number
+/// (inf + -inf is the left one), the other results are saturated
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Saturating<T>(T);
+
+macro_rules! impl_saturating {
+    ($($t:ty),+) => {
+        $(
+            impl Saturating<$t> {
+                /// return Err if `x` is MAX or MIN, they are kept for +/- infinity
+                pub fn new(x: $t) -> Result<Self, GraphError> {
+                    if x == <$t>::MAX || x == <$t>::MIN {
+                        Err(GraphError::InvalidWeight(format!("{}", x)))
+                    } else {
+                        Ok(Self(x))
+                    }
+                }
+
+                pub fn get(self) -> $t {
+                    self.0
+                }
+
+                pub fn neg_infinity() -> Self {
+                    Self(<$t>::MIN)
+                }
+
+                fn is_infinite(self) -> bool {
+                    self.0 == <$t>::MAX || self.0 == <$t>::MIN
+                }
+            }
+
+            impl TryFrom<$t> for Saturating<$t> {
+                type Error = GraphError;
+
+                fn try_from(x: $t) -> Result<Self, GraphError> {
+                    Self::new(x)
+                }
+            }
+
+            impl Neg for Saturating<$t> {
+                type Output = Self;
+
+                /// -MIN is MAX, rather than overflow
+                fn neg(self) -> Self {
+                    if self.0 == <$t>::MIN {
+                        Self(<$t>::MAX)
+                    } else {
+                        Self(-self.0)
+                    }
+                }
+            }
+
+            impl Add for Saturating<$t> {
+                type Output = Self;
+
+                fn add(self, rhs: Self) -> Self {
+                    if self.is_infinite() {
+                        self
+                    } else if rhs.is_infinite() {
+                        rhs
+                    } else {
+                        Self(self.0.saturating_add(rhs.0))
+                    }
+                }
+            }
+
+            impl Sub for Saturating<$t> {
+                type Output = Self;
+
+                fn sub(self, rhs: Self) -> Self {
+                    self + (-rhs)
+                }
+            }
+
+            impl AddAssign for Saturating<$t> {
+                fn add_assign(&mut self, rhs: Self) {
+                    *self = *self + rhs;
+                }
+            }
+
+            impl SubAssign for Saturating<$t> {
+                fn sub_assign(&mut self, rhs: Self) {
+                    *self = *self - rhs;
+                }
+            }
+
+            impl fmt::Debug for Saturating<$t> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self)
+                }
+            }
+
+            impl fmt::Display for Saturating<$t> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match self.0 {
+                        <$t>::MAX => write!(f, "inf"),
+                        <$t>::MIN => write!(f, "-inf"),
+                        x => write!(f, "{}", x),
+                    }
+                }
+            }
+
+            impl Weight for Saturating<$t> {
+                fn infinity() -> Self {
+                    Self(<$t>::MAX)
+                }
+
+                /// never overflow
+                fn saturating_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+            }
+        )+
+    };
+}
+
+impl_saturating!(i32, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dijkstra, dinic_new, MakeGraph, MapWeight};
+
+    #[test]
+    fn test_ordered_f64() {
+        assert!(OrderedF64::new(f64::NAN).is_err());
+        let (a, b) = (OrderedF64::new(1.5).unwrap(), OrderedF64::try_from(-2.0).unwrap());
+        assert_eq!((a + b).get(), -0.5);
+        assert_eq!(a.max(b), a);
+        let neg_inf = OrderedF64::new(f64::NEG_INFINITY).unwrap();
+        assert_eq!(OrderedF64::infinity().checked_add(neg_inf), None);
+        let min = OrderedF64::new(f64::MIN).unwrap();
+        assert_eq!(min.saturating_add(min), OrderedF64::infinity());
+
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let g2 = MapWeight::new(&g, |_u, _v, w| OrderedF64::new(w as f64 / 2.0).unwrap());
+        let lhs: i32 = dijkstra::dijkstra(&g, 0).map(|e| e.dist).sum();
+        let rhs: f64 = dijkstra::dijkstra(&g2, 0).map(|e| e.dist.get()).sum();
+        assert_eq!(lhs as f64 / 2.0, rhs);
+    }
+
+    #[test]
+    fn test_rational() {
+        assert!(Rational::new(1, 0).is_err());
+        assert!(Rational::new(i64::MAX, 1).is_err());
+        assert!(Rational::new(i64::MIN, 1).is_err());
+        assert!(Rational::new(-i64::MAX, 1).is_err());
+        assert!(Rational::new(i64::MAX, 2).is_ok());
+        let a = Rational::new(2, -4).unwrap();
+        assert_eq!((a.numer(), a.denom()), (-1, 2));
+        assert_eq!(a + Rational::new(1, 3).unwrap(), Rational::new(-1, 6).unwrap());
+        assert_eq!(a - a, Rational::zero());
+        assert!(a < Rational::new(-1, 3).unwrap());
+        assert_eq!(Rational::infinity() - Rational::from_integer(1).unwrap(), Rational::infinity());
+        assert!(Rational::from_integer(i64::MIN).is_err());
+        assert!(Rational::try_from(i64::MIN).is_err());
+        assert!(Rational::try_from(i64::MAX).is_err());
+        let half = Rational::new(1, 2).unwrap();
+        assert_eq!(Rational::from_integer(i64::MAX - 1).unwrap().checked_add(half), None);
+        assert_eq!(format!("{}", Rational::new(6, 4).unwrap()), "3/2");
+
+        // every capacity / 3, then the max flow is exactly 23 / 3
+        let (g, _s_lst) = MakeGraph::mf();
+        let g2 = MapWeight::new(&g, |_u, _v, w| Rational::new(w as i64, 3).unwrap());
+        let flowing = dinic_new::dinic(&g2, 0, 5);
+        let flow = flowing[&5].values().fold(Rational::zero(), |acc, w| acc + *w);
+        assert_eq!(flow, Rational::new(23, 3).unwrap());
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert!(Saturating::<i64>::new(i64::MAX).is_err());
+        assert!(Saturating::<i32>::try_from(i32::MIN).is_err());
+        let a = Saturating::<i64>::new(i64::MAX - 1).unwrap();
+        let one = Saturating::<i64>::new(1).unwrap();
+        assert_eq!(a + one, Saturating::infinity());
+        assert_eq!(a + one + one - one, Saturating::infinity());
+        assert_eq!(-a - one - one, Saturating::<i64>::neg_infinity());
+        assert_eq!(a.checked_add(one), Some(Saturating::infinity()));
+        assert_eq!(format!("{}", a + a), "inf");
+        assert_eq!((a - one).get(), i64::MAX - 2);
+    }
+}
@@ -0,0 +1,239 @@
+/// multi-threaded (rayon) variants of some algorithms, enabled by feature "parallel"
+/// every function returns exactly the same result as its sequential version,
+/// so they can be swapped freely
+use crate::floyd_warshall::check_overflow;
+use crate::johnson::{calc_h, check_reweight, johnson_from, reweight};
+use crate::kruskal::DisjointSet;
+use crate::view::MapWeight;
+use crate::weight::{overflowed_rows, OverflowTracker};
+use crate::{Graph, GraphError, TreeEdge, VertexId, Weight};
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// the same as johnson, dijkstra from every source runs in parallel
+/// NOTE: panic if negative cycle existed or overflowed, use try_par_johnson to avoid it
+#[allow(clippy::type_complexity)]
+pub fn par_johnson<G>(graph: &G) -> Vec<(Vec<Option<G::Weight>>, Vec<VertexId>)>
+where
+    G: Graph + Sync,
+    G::Weight: Send + Sync,
+{
+    try_par_johnson(graph).expect("Negative cycle found or overflowed!")
+}
+
+#[allow(clippy::type_complexity)]
+pub fn try_par_johnson<G>(graph: &G) -> Result<Vec<(Vec<Option<G::Weight>>, Vec<VertexId>)>, GraphError>
+where
+    G: Graph + Sync,
+    G::Weight: Send + Sync,
+{
+    let h = calc_h(graph)?;
+    check_reweight(graph, &h)?;
+    let g2 = MapWeight::new(graph, |u, v, w| reweight(w, h[u], h[v]).unwrap()); // checked
+    (0..graph.len())
+        .into_par_iter()
+        .map(|i| johnson_from(&g2, &h, i))
+        .collect()
+}
+
+/// the same as floyd_warshall, for every i, the rows u (u != i) are relaxed in parallel,
+/// it's safe since row i never changes in round i
+pub fn par_floyd_warshall<G>(graph: &G) -> Vec<Vec<Option<G::Weight>>>
+where
+    G: Graph,
+    G::Weight: Send + Sync,
+{
+    par_floyd_warshall_dist(init_dist(graph)).0
+}
+
+/// return Err if negative cycle existed (some dist[u][u] < 0) or some distance overflowed
+#[allow(clippy::type_complexity)]
+pub fn try_par_floyd_warshall<G>(graph: &G) -> Result<Vec<Vec<Option<G::Weight>>>, GraphError>
+where
+    G: Graph,
+    G::Weight: Send + Sync,
+{
+    check_overflow(par_floyd_warshall_dist(init_dist(graph)))
+}
+
+/// the same init dist as floyd_warshall: dist[u][u] is zero unless a negative self-loop,
+/// the minimum of parallel edges
+fn init_dist<G: Graph>(graph: &G) -> Vec<Vec<Option<G::Weight>>> {
+    let n = graph.len();
+    let mut dist = vec![vec![None; n]; n];
+    for (u, dist_u) in dist.iter_mut().enumerate() {
+        dist_u[u] = Some(Default::default());
+        for (v, w) in graph.iter_e_from(u) {
+            dist_u[v] = Some(dist_u[v].map_or(w, |d: G::Weight| d.min(w)));
+        }
+    }
+    dist
+}
+
+#[allow(clippy::type_complexity)]
+fn par_floyd_warshall_dist<W: Weight + Send + Sync>(mut dist: Vec<Vec<Option<W>>>) -> (Vec<Vec<Option<W>>>, bool) {
+    let n = dist.len();
+    let mut trackers: Vec<_> = (0..n).map(|_| OverflowTracker::new(n)).collect();
+    for i in 0..n {
+        let dist_i = dist[i].clone();
+        dist.par_iter_mut().zip(trackers.par_iter_mut()).enumerate().for_each(|(u, (dist_u, tracker))| {
+            if u == i {
+                return;
+            }
+            for (v, od2) in dist_i.iter().enumerate() {
+                // dist_u[i] may be changed when v == i, read it every time (as floyd_warshall)
+                if let (Some(d1), Some(d2)) = (dist_u[i], *od2) {
+                    if let Some(d) = tracker.add(d1, d2, v) {
+                        let can_improve = match dist_u[v] {
+                            None => true,
+                            Some(d0) => d < d0,
+                        };
+                        if can_improve {
+                            dist_u[v] = Some(d);
+                        }
+                    }
+                }
+            }
+        });
+    }
+    let overflowed = overflowed_rows(&trackers, &dist);
+    (dist, overflowed)
+}
+
+/// level-synchronous bfs: the next level is found in parallel,
+/// then merged in the order of the current level, so the order is the same as bfs
+pub fn par_bfs<G: Graph + Sync>(graph: &G, start: impl Into<VertexId>) -> Vec<usize> {
+    let start = start.into().index();
+    let mut visited = vec![false; graph.len()];
+    visited[start] = true;
+    let mut res = vec![start];
+    let mut level = vec![start];
+    while !level.is_empty() {
+        let found: Vec<Vec<usize>> = level
+            .par_iter()
+            .map(|&u| graph.iter_v_from(u).filter(|&v| !visited[v]).collect())
+            .collect();
+        level.clear();
+        for v in found.into_iter().flatten() {
+            if !visited[v] {
+                visited[v] = true;
+                level.push(v);
+            }
+        }
+        res.extend_from_slice(&level);
+    }
+    res
+}
+
+/// borůvka: in every round, each component picks its lightest edge in parallel,
+/// there are at most logV rounds, O(ElogV)
+/// edges are ordered by (w, u, v) (the same as kruskal), so the spanning tree is unique,
+/// and it's returned in the order of kruskal too
+/// NOTE: like kruskal, a directed edge is considered as undirected
+pub fn par_boruvka<G>(graph: &G) -> Vec<TreeEdge<G::Weight>>
+where
+    G: Graph + Sync,
+    G::Weight: Send + Sync,
+{
+    let n = graph.len();
+    let mut edges: Vec<(G::Weight, usize, usize)> = (0..n)
+        .into_par_iter()
+        .flat_map_iter(|u| graph.iter_e_from(u).map(move |(v, w)| (w, u, v)))
+        .collect();
+    edges.par_sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut ds = DisjointSet::new(n);
+    let mut comp: Vec<usize> = (0..n).collect();
+    let mut used = vec![false; edges.len()];
+    loop {
+        // an edge's index is its rank, so the lightest edge is the one of minimum index
+        let best: Vec<AtomicUsize> = (0..n).map(|_| AtomicUsize::new(usize::MAX)).collect();
+        edges.par_iter().enumerate().for_each(|(i, &(_, u, v))| {
+            let (a, b) = (comp[u], comp[v]);
+            if a != b {
+                best[a].fetch_min(i, AtomicOrdering::Relaxed);
+                best[b].fetch_min(i, AtomicOrdering::Relaxed);
+            }
+        });
+        let mut merged = false;
+        for i in best.into_iter().map(AtomicUsize::into_inner) {
+            if i != usize::MAX && ds.union(edges[i].1, edges[i].2) {
+                used[i] = true;
+                merged = true;
+            }
+        }
+        if !merged {
+            break;
+        }
+        for (u, c) in comp.iter_mut().enumerate() {
+            *c = ds.find_set(u);
+        }
+    }
+    edges
+        .into_iter()
+        .zip(used)
+        .filter(|(_, used)| *used)
+        .map(|((w, u, v), _)| TreeEdge::new(u, v, w))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bfs, floyd_warshall, johnson, kruskal, MakeGraph, VGraph};
+    use std::collections::HashMap;
+
+    /// xorshift graph, with parallel weights to make ties
+    fn random_graph(n: usize, m: usize) -> VGraph<i64> {
+        let mut x: u64 = 88172645463325252;
+        let mut next = move || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as usize
+        };
+        let mut lst = vec![HashMap::new(); n];
+        for _ in 0..m {
+            let (u, v) = (next() % n, next() % n);
+            lst[u].insert(v, (next() % 20) as i64);
+        }
+        VGraph::new(lst)
+    }
+
+    #[test]
+    fn test_parallel() {
+        let (g, _s_lst) = MakeGraph::spn();
+        assert_eq!(par_johnson(&g), johnson::johnson(&g));
+        assert_eq!(par_floyd_warshall(&g), floyd_warshall::floyd_warshall(&g));
+        let (g, _s_lst) = MakeGraph::mst(true);
+        assert_eq!(par_boruvka(&g), kruskal::kruskal(&g));
+        let (g, _s_lst) = MakeGraph::scc();
+        assert_eq!(par_bfs(&g, 0), bfs::bfs(&g, 0).collect::<Vec<_>>());
+
+        let g = random_graph(300, 3000);
+        assert_eq!(par_johnson(&g), johnson::johnson(&g));
+        assert_eq!(par_floyd_warshall(&g), floyd_warshall::floyd_warshall(&g));
+        assert_eq!(par_boruvka(&g), kruskal::kruskal(&g));
+        for start in [0, 17, 299] {
+            assert_eq!(par_bfs(&g, start), bfs::bfs(&g, start).collect::<Vec<_>>());
+        }
+
+        let g = VGraph::new(vec![HashMap::from([(1, 1)]), HashMap::from([(0, -2)])]);
+        assert_eq!(try_par_johnson(&g), Err(GraphError::NegativeCycle));
+        assert_eq!(try_par_floyd_warshall(&g), Err(GraphError::NegativeCycle));
+        let g = VGraph::new(vec![HashMap::from([(1, 200u8)]), HashMap::from([(2, 100)]), HashMap::new()]);
+        assert_eq!(try_par_floyd_warshall(&g), Err(GraphError::Overflow));
+        assert_eq!(par_floyd_warshall(&g), floyd_warshall::floyd_warshall(&g));
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 200u8), (2, 10)]),
+            HashMap::from([(3, 100)]),
+            HashMap::from([(1, 10)]),
+            HashMap::new(),
+        ]);
+        assert_eq!(try_par_floyd_warshall(&g), floyd_warshall::try_floyd_warshall(&g));
+        assert!(try_par_floyd_warshall(&g).is_ok());
+        let g = VGraph::new(vec![HashMap::from([(1, -100i8), (2, 100)]), HashMap::from([(2, -27)]), HashMap::new()]);
+        assert_eq!(try_par_johnson(&g), Err(GraphError::Overflow));
+    }
+}
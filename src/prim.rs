@@ -1,62 +1,91 @@
-use crate::Graph;
-use utils::Heap;
+use crate::{Graph, GraphError, IndexType, TreeEdge};
+use crate::heap::IndexedHeap;
 
 /// run prim on directed graph (need to add reverse edges)
 /// it is only can be used on the graph,
 /// which exist (a -> b: w) then (b -> a: w),
 /// otherwise we will get bad result
+/// NOTE: UGraph always promise this
 /// O((E+V)logV)
-pub fn prim<G: Graph>(graph: &G) -> Vec<(G::Weight, usize, usize)> {
+pub fn prim<G: Graph>(graph: &G) -> Vec<TreeEdge<G::Weight>> {
+    if graph.is_empty() {
+        return vec![];
+    }
     PrimIter::new(graph).collect()
 }
 
+/// return Err if the graph is empty (no spanning tree at all)
+pub fn try_prim<G: Graph>(graph: &G) -> Result<Vec<TreeEdge<G::Weight>>, GraphError> {
+    if graph.is_empty() {
+        Err(GraphError::EmptyGraph)
+    } else {
+        Ok(prim(graph))
+    }
+}
+
+/// key of a vertex: the minimum weight of edges from the tree to it
 struct PrimIter<'a, G: Graph> {
     graph: &'a G,
     used: Vec<bool>,
-    heap: Heap<(G::Weight, usize, usize)>,
+    from: Vec<G::Index>,
+    heap: IndexedHeap<(G::Weight, G::Index), G::Index>, // (weight, vertex)
 }
 
 impl<'a, G: Graph> PrimIter<'a, G> {
     fn new(graph: &'a G) -> Self {
         let start = 0;
-        let mut heap = Heap::new();
-        for (v, w) in graph.iter_e_from(start) {
-            heap.push((w, v, start));
-        }
-        let mut used = vec![false; graph.len()];
+        let n = graph.len();
+        let mut used = vec![false; n];
         used[start] = true;
-        Self { graph, used, heap }
+        let mut res = Self {
+            graph,
+            used,
+            from: vec![G::Index::new(start); n],
+            heap: IndexedHeap::with_index(n, 2),
+        };
+        res.relax(start);
+        res
+    }
+
+    fn relax(&mut self, u: usize) {
+        for (v, w) in self.graph.iter_e_from(u) {
+            if !self.used[v] && self.heap.decrease_key(v, (w, G::Index::new(v))) {
+                self.from[v] = G::Index::new(u);
+            }
+        }
     }
 }
 
 impl<'a, G: Graph> Iterator for PrimIter<'a, G> {
-    type Item = (G::Weight, usize, usize);
+    type Item = TreeEdge<G::Weight>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some((w, u, v)) = self.heap.pop() {
-            if !self.used[u] {
-                self.used[u] = true;
-                for (v, w) in self.graph.iter_e_from(u) {
-                    self.heap.push((w, v, u));
-                }
-                return Some((w, u, v)); // NOTE: v, w is ok
-            }
-        }
-        None
+        let (u, (w, _)) = self.heap.pop()?;
+        self.used[u] = true;
+        self.relax(u);
+        Some(TreeEdge::new(self.from[u].index(), u, w))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, UGraph};
 
     #[test]
     fn test_prim() {
         let (g, s_lst) = MakeGraph::mst(true);
         let res = g.prim();
-        assert_eq!(res.iter().map(|(w, _u, _v)| *w).sum::<i32>(), 37);
-        for (w, u, v) in res.into_iter() {
-            println!("weight: {}, from: {}, to: {}", w, s_lst[u], s_lst[v]);
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37);
+        for TreeEdge { from, to, weight } in res.into_iter() {
+            println!("weight: {}, from: {}, to: {}", weight, s_lst[from.index()], s_lst[to.index()]);
         }
     }
+
+    #[test]
+    fn test_prim_empty() {
+        let g: UGraph<i32> = UGraph::new(0);
+        assert!(prim(&g).is_empty());
+        assert_eq!(try_prim(&g), Err(GraphError::EmptyGraph));
+    }
 }
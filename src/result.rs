@@ -0,0 +1,248 @@
+/// structured results of algorithms, which are easy to keep (e.g. with feature "serde")
+/// rather than tuples and nested HashMaps
+use crate::{Graph, VertexId, Weight};
+use std::collections::HashMap;
+
+/// an edge of a spanning tree (kruskal, prim, boruvka),
+/// V is the type of vertices: VertexId, or String for LabeledGraph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeEdge<W, V = VertexId> {
+    pub from: V,
+    pub to: V,
+    pub weight: W,
+}
+
+impl<W> TreeEdge<W> {
+    pub fn new(from: impl Into<VertexId>, to: impl Into<VertexId>, weight: W) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            weight,
+        }
+    }
+}
+
+impl<W, V> TreeEdge<W, V> {
+    /// the same edge with every vertex mapped by `f`, e.g. to its label
+    pub fn map_vertex<U>(self, mut f: impl FnMut(V) -> U) -> TreeEdge<W, U> {
+        TreeEdge {
+            from: f(self.from),
+            to: f(self.to),
+            weight: self.weight,
+        }
+    }
+}
+
+/// a vertex reached by a shortest path search (dijkstra, a_star):
+/// `dist` is the distance of `to`, `from` is the vertex before `to` on the path,
+/// V is the type of vertices as in TreeEdge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathEdge<W, V = VertexId> {
+    pub from: V,
+    pub to: V,
+    pub dist: W,
+}
+
+impl<W> PathEdge<W> {
+    pub fn new(from: impl Into<VertexId>, to: impl Into<VertexId>, dist: W) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            dist,
+        }
+    }
+}
+
+impl<W, V> PathEdge<W, V> {
+    /// the same edge with every vertex mapped by `f`, e.g. to its label
+    pub fn map_vertex<U>(self, mut f: impl FnMut(V) -> U) -> PathEdge<W, U> {
+        PathEdge {
+            from: f(self.from),
+            to: f(self.to),
+            dist: self.dist,
+        }
+    }
+}
+
+/// shortest path tree from `start`, built from (dist, from) of bellman_ford, spfa, johnson...
+/// dist[v] is None if v is unreachable, from[v] is the vertex before v (from[start] == start)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShortestPathTree<W> {
+    pub start: VertexId,
+    pub dist: Vec<Option<W>>,
+    pub from: Vec<VertexId>,
+}
+
+impl<W: Weight> ShortestPathTree<W> {
+    pub fn new(start: impl Into<VertexId>, dist: Vec<Option<W>>, from: Vec<VertexId>) -> Self {
+        let start = start.into();
+        Self { start, dist, from }
+    }
+
+    /// run dijkstra and collect its output
+    pub fn dijkstra<G: Graph<Weight = W>>(graph: &G, start: impl Into<VertexId>) -> Self {
+        let start = start.into();
+        let n = graph.len();
+        let mut dist = vec![None; n];
+        let mut from = vec![start; n];
+        dist[start.index()] = Some(W::zero());
+        for e in crate::dijkstra::dijkstra(graph, start) {
+            dist[e.to.index()] = Some(e.dist);
+            from[e.to.index()] = e.from;
+        }
+        Self::new(start, dist, from)
+    }
+
+    /// vertices from start to `v`, None if `v` is unreachable
+    pub fn path_to(&self, v: impl Into<VertexId>) -> Option<Vec<VertexId>> {
+        let v = v.into();
+        self.dist.get(v.index()).copied().flatten()?;
+        let mut res = vec![v];
+        let mut u = v;
+        while u != self.start {
+            u = self.from[u.index()];
+            res.push(u);
+        }
+        res.reverse();
+        Some(res)
+    }
+}
+
+/// flow of a max flow from `start` to `target`,
+/// `flows` are (u, v, flow on u -> v) without zero flows, sorted by (u, v)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowAssignment<W> {
+    pub start: usize,
+    pub target: usize,
+    pub value: W,
+    pub flows: Vec<(usize, usize, W)>,
+}
+
+impl<W: Weight> FlowAssignment<W> {
+    /// `flowing` is the output of edmonds_karp or dinic_new: flowing[v][u] is the flow on u -> v
+    pub fn from_flowing(
+        start: impl Into<VertexId>,
+        target: impl Into<VertexId>,
+        flowing: &HashMap<usize, HashMap<usize, W>>,
+    ) -> Self {
+        let start = start.into().index();
+        let target = target.into().index();
+        let mut flows: Vec<(usize, usize, W)> = flowing
+            .iter()
+            .flat_map(|(&v, dct)| dct.iter().map(move |(&u, &w)| (u, v, w)))
+            .filter(|(_, _, w)| !w.is_zero())
+            .collect();
+        flows.sort_by_key(|&(u, v, _)| (u, v));
+        let mut value = W::zero();
+        for &(u, v, w) in flows.iter() {
+            if u == start {
+                value += w;
+            }
+            if v == start {
+                value -= w;
+            }
+        }
+        Self {
+            start,
+            target,
+            value,
+            flows,
+        }
+    }
+
+    /// flow on u -> v
+    pub fn flow(&self, u: usize, v: usize) -> W {
+        match self.flows.binary_search_by_key(&(u, v), |&(a, b, _)| (a, b)) {
+            Ok(i) => self.flows[i].2,
+            Err(_) => W::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bellman_ford, dinic_new, MakeGraph};
+
+    #[test]
+    fn test_result() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let tree = ShortestPathTree::dijkstra(&g, 0);
+        let (_state, dist, from) = bellman_ford::bellman_ford(&g, 0);
+        assert_eq!(tree.dist, dist);
+        let other = ShortestPathTree::new(0, dist, from);
+        for v in 0..g.len() {
+            let path = tree.path_to(v).unwrap();
+            assert_eq!(path.first(), Some(&VertexId(0)));
+            assert_eq!(path.last(), Some(&VertexId(v)));
+            assert_eq!(other.path_to(v).unwrap().last(), Some(&VertexId(v)));
+        }
+
+        let (g, _s_lst) = MakeGraph::mf();
+        let flowing = g.edmonds_karp(0, 5);
+        let res = FlowAssignment::from_flowing(0, 5, &flowing);
+        assert_eq!(res.value, 23);
+        assert_eq!(FlowAssignment::from_flowing(0, 5, &dinic_new::dinic(&g, 0, 5)).value, 23);
+        for &(u, v, w) in res.flows.iter() {
+            assert_eq!(res.flow(u, v), w);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::{CsrGraph, Edge, FlowEdge, Graph, LabeledGraph, MultiGraph, Rational, VGraph};
+
+        let (g, _s_lst) = MakeGraph::mf();
+        let json = serde_json::to_string(&g).unwrap();
+        let g2: VGraph<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(g2.scc(), g.scc());
+        assert!(serde_json::from_str::<VGraph<i32>>(r#"{"lst":[{"5":1}]}"#).is_err());
+        let many = format!(r#"{{"lst":[{}]}}"#, vec!["{}"; u16::MAX as usize + 1].join(","));
+        assert!(serde_json::from_str::<VGraph<i32, u16>>(&many).is_err());
+
+        let csr = CsrGraph::from_graph(&g);
+        let json = serde_json::to_string(&csr).unwrap();
+        let csr2: CsrGraph<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(csr2.iter_e_all().collect::<Vec<_>>(), csr.iter_e_all().collect::<Vec<_>>());
+        assert!(serde_json::from_str::<CsrGraph<i32>>(r#"{"n":1,"edges":[[0,5,1]]}"#).is_err());
+
+        let eg = MultiGraph::new(vec![(0, 1, 2), (0, 1, 3)], 2);
+        let json = serde_json::to_string(&eg).unwrap();
+        let eg2: MultiGraph<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(eg2.iter_e_from(0).collect::<Vec<_>>(), vec![(1, 2), (1, 3)]);
+        assert!(serde_json::from_str::<MultiGraph<i32>>(r#"{"e_lst":[[0,5,1]],"n":1}"#).is_err());
+
+        let res = FlowAssignment::from_flowing(0, 5, &g.edmonds_karp(0, 5));
+        let json = serde_json::to_string(&res).unwrap();
+        assert_eq!(serde_json::from_str::<FlowAssignment<i32>>(&json).unwrap(), res);
+        let tree = ShortestPathTree::dijkstra(&g, 0);
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(serde_json::from_str::<ShortestPathTree<i32>>(&json).unwrap(), tree);
+
+        let e = FlowEdge::new(0, 1, Rational::new(2, 4).unwrap());
+        let json = serde_json::to_string(&e).unwrap();
+        assert_eq!(json, r#"{"from":0,"to":1,"cap":[1,2],"flow":[0,1]}"#);
+        let e2: FlowEdge<Rational> = serde_json::from_str(&json).unwrap();
+        assert_eq!(e2.get_weight(), &Rational::new(1, 2).unwrap());
+        assert!(serde_json::from_str::<Rational>("[1,0]").is_err());
+        assert!(serde_json::from_str::<Rational>("[-9223372036854775808,1]").is_err());
+
+        let mut lg: LabeledGraph<u8, i32> = LabeledGraph::new();
+        lg.add_vertex("a", 1).unwrap();
+        lg.add_vertex("b", 2).unwrap();
+        lg.add_edge_by_label("a", "b", 3).unwrap();
+        let json = serde_json::to_string(&lg).unwrap();
+        let lg2: LabeledGraph<u8, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(lg2.index_of("b"), Ok(1));
+        assert_eq!(lg2.vertex(1).borrow().data, 2);
+        let e = PathEdge::new(0, 1, 3).map_vertex(|u| lg2.label(u));
+        assert_eq!(lg2.dijkstra("a").unwrap(), vec![e]);
+        let dup = json.replace(r#""label":"b""#, r#""label":"a""#);
+        assert!(serde_json::from_str::<LabeledGraph<u8, i32>>(&dup).is_err());
+    }
+}
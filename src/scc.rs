@@ -1,25 +1,25 @@
-use crate::{Graph, NoWeight, VGraph}; // topo_sort_dfs,
-use std::collections::HashMap;
+use crate::topo_sort::topo_sort_dfs;
+use crate::view::Reversed;
+use crate::{CsrGraph, Graph, IndexType, NoWeight};
 
+/// O(V + E): topo_sort_dfs runs on the Reversed view if iter_v_to of `graph` is cheap,
+/// otherwise (such as VGraph without reverse index) on a reversed CsrGraph built once
 pub fn scc<G: Graph>(graph: &G) -> Vec<Vec<usize>> {
     let n = graph.len();
 
-    // use the simplest VGraph (to reverse) no matter graph is
-    let mut lst = vec![HashMap::new(); n];
-    for u in 0..n {
-        for v in graph.iter_v_from(u) {
-            lst[v].insert(u, NoWeight);
-        }
-    }
-    let graph_rev = VGraph::new(lst);
-
-    let seq = graph_rev.topo_sort_dfs();
+    let seq = if graph.has_fast_iter_to() {
+        topo_sort_dfs(&Reversed::new(graph))
+    } else {
+        // CsrGraph keeps edges in order, so scc stays deterministic on a deterministic graph
+        let edges = graph.iter_e_all().map(|(u, v, _)| (v, u, NoWeight));
+        topo_sort_dfs(&CsrGraph::<NoWeight>::from_edges(n, edges))
+    };
     let mut visited = vec![false; n];
     let mut res = vec![];
 
     for i in seq.into_iter().rev() {
         if !visited[i] {
-            res.push(DfsIter::new(graph, i, &mut visited).into_iter().collect());
+            res.push(DfsIter::new(graph, i, &mut visited).collect());
         }
     }
     res
@@ -30,14 +30,14 @@ pub fn scc<G: Graph>(graph: &G) -> Vec<Vec<usize>> {
 /// visted: Vec<bool> vs &'a mut Vec<bool>
 struct DfsIter<'a, G: Graph> {
     visited: &'a mut Vec<bool>,
-    stack: Vec<usize>,
+    stack: Vec<G::Index>,
     graph: &'a G,
 }
 
 impl<'a, G: Graph> DfsIter<'a, G> {
     fn new(graph: &'a G, start: usize, visited: &'a mut Vec<bool>) -> Self {
         visited[start] = true;
-        let stack = vec![start];
+        let stack = vec![G::Index::new(start)];
         Self {
             visited,
             stack,
@@ -51,10 +51,11 @@ impl<'a, G: Graph> Iterator for DfsIter<'a, G> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(u) = self.stack.pop() {
+            let u = u.index();
             for v in self.graph.iter_v_from(u) {
                 if !self.visited[v] {
                     self.visited[v] = true;
-                    self.stack.push(v);
+                    self.stack.push(G::Index::new(v));
                 }
             }
             Some(u)
@@ -66,6 +67,7 @@ impl<'a, G: Graph> Iterator for DfsIter<'a, G> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::MakeGraph;
 
     #[test]
@@ -78,5 +80,39 @@ mod tests {
             }
             println!();
         }
+
+        // the same components with or without a cheap iter_v_to
+        let (mut g2, _s_lst) = MakeGraph::scc();
+        g2.build_reverse_index();
+        assert!(!g.has_fast_iter_to() && g2.has_fast_iter_to());
+        let sorted = |mut parts: Vec<Vec<usize>>| {
+            parts.iter_mut().for_each(|p| p.sort());
+            parts.sort();
+            parts
+        };
+        assert_eq!(sorted(g.scc()), sorted(g2.scc()));
+    }
+
+    #[test]
+    fn test_scc_sorted() {
+        use crate::GraphBuilder;
+
+        // several components, edges from a simple lcg
+        let n = 30;
+        let mut x: u64 = 7;
+        let mut edges = vec![];
+        for _ in 0..60 {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let (u, v) = ((x >> 33) as usize % n, (x >> 17) as usize % n);
+            if u != v {
+                edges.push((u, v, NoWeight));
+            }
+        }
+        let build = || GraphBuilder::new(n).edges(edges.clone()).build_sorted_vgraph().unwrap();
+        let res = scc(&build());
+        assert!(res.len() > 2 && res.iter().any(|p| p.len() > 1));
+        for _ in 0..20 {
+            assert_eq!(scc(&build()), res);
+        }
     }
 }
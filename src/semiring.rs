@@ -0,0 +1,569 @@
+/// path algebra: a path's value is the `times` of its edges' values,
+/// the value between two vertices is the `plus` of all paths' values
+/// shortest path is the (min, +) semiring, others:
+/// Widest: bottleneck path (max, min)
+/// Reliability: most reliable path (max, *)
+/// Counting: number of shortest paths
+/// Reachable: boolean reachability (or, and)
+/// all solvers take an `edge(u, v, w)` function, which lift an edge's weight to the semiring,
+/// dijkstra, bellman_ford and floyd_warshall are these solvers over MinPlus
+use crate::error::GraphError;
+use crate::heap::IndexedHeap;
+use crate::index::vertex_ids;
+use crate::weight::{overflowed_rows, OverflowTracker};
+use crate::{Graph, IndexType, PathEdge, VertexId, Weight};
+use std::cmp::Ordering;
+use std::fmt;
+
+pub trait Semiring: Copy + PartialEq + fmt::Debug {
+    /// identity of plus: no path
+    fn zero() -> Self;
+
+    /// identity of times: the empty path
+    fn one() -> Self;
+
+    /// combine two alternative paths
+    fn plus(self, rhs: Self) -> Self;
+
+    /// extend a path by an edge
+    fn times(self, rhs: Self) -> Self;
+
+    /// None if the value of the extended path can not be held (e.g. integer overflow),
+    /// the solvers ignore such a path, and record it (see OverflowTracker)
+    fn checked_times(self, rhs: Self) -> Option<Self> {
+        Some(self.times(rhs))
+    }
+}
+
+/// plus chooses the better one (ties may be merged, see Counting),
+/// and times never make a path better, so the semiring can be solved by dijkstra
+pub trait OrderedSemiring: Semiring {
+    /// strictly better
+    fn better(&self, other: &Self) -> bool;
+}
+
+/// shortest path: zero is infinity (no path), times saturates, checked_times does not
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MinPlus<W>(pub W);
+
+impl<W: Weight> Semiring for MinPlus<W> {
+    fn zero() -> Self {
+        Self(W::infinity())
+    }
+
+    fn one() -> Self {
+        Self(W::zero())
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        Self(self.0.min(rhs.0))
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    fn checked_times(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+}
+
+impl<W: Weight> OrderedSemiring for MinPlus<W> {
+    fn better(&self, other: &Self) -> bool {
+        self.0 < other.0
+    }
+}
+
+/// widest (bottleneck) path: the capacity of a path is its minimum edge
+/// NOTE: zero is W::zero(), so a path of capacity zero is the same as no path
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Widest<W>(pub W);
+
+impl<W: Weight> Semiring for Widest<W> {
+    fn zero() -> Self {
+        Self(W::zero())
+    }
+
+    fn one() -> Self {
+        Self(W::infinity())
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        if self.0 < rhs.0 { rhs } else { self }
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        Self(self.0.min(rhs.0))
+    }
+}
+
+impl<W: Weight> OrderedSemiring for Widest<W> {
+    fn better(&self, other: &Self) -> bool {
+        self.0 > other.0
+    }
+}
+
+/// most reliable path: every edge works with a probability (in [0, 1])
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Reliability(f64);
+
+impl Reliability {
+    /// return Err if `p` is not in [0, 1]
+    pub fn new(p: f64) -> Result<Self, GraphError> {
+        if (0.0..=1.0).contains(&p) {
+            Ok(Self(p))
+        } else {
+            Err(GraphError::InvalidWeight(format!("{}", p)))
+        }
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl Semiring for Reliability {
+    fn zero() -> Self {
+        Self(0.0)
+    }
+
+    fn one() -> Self {
+        Self(1.0)
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        if self.0 < rhs.0 { rhs } else { self }
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl OrderedSemiring for Reliability {
+    fn better(&self, other: &Self) -> bool {
+        self.0 > other.0
+    }
+}
+
+/// (shortest distance, number of shortest paths)
+/// NOTE: plus is not idempotent, so it can only be solved by dijkstra (positive weights)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Counting<W> {
+    pub dist: W,
+    pub count: u64,
+}
+
+impl<W: Weight> Counting<W> {
+    /// a single edge
+    pub fn new(dist: W) -> Self {
+        Self { dist, count: 1 }
+    }
+}
+
+impl<W: Weight> Semiring for Counting<W> {
+    fn zero() -> Self {
+        Self {
+            dist: W::infinity(),
+            count: 0,
+        }
+    }
+
+    fn one() -> Self {
+        Self {
+            dist: W::zero(),
+            count: 1,
+        }
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        if self.dist < rhs.dist {
+            self
+        } else if rhs.dist < self.dist {
+            rhs
+        } else {
+            Self {
+                dist: self.dist,
+                count: self.count.saturating_add(rhs.count),
+            }
+        }
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        Self {
+            dist: self.dist.saturating_add(rhs.dist),
+            count: self.count.saturating_mul(rhs.count),
+        }
+    }
+
+    fn checked_times(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            dist: self.dist.checked_add(rhs.dist)?,
+            count: self.count.saturating_mul(rhs.count),
+        })
+    }
+}
+
+impl<W: Weight> OrderedSemiring for Counting<W> {
+    fn better(&self, other: &Self) -> bool {
+        self.dist < other.dist
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Reachable(pub bool);
+
+impl Semiring for Reachable {
+    fn zero() -> Self {
+        Self(false)
+    }
+
+    fn one() -> Self {
+        Self(true)
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        Self(self.0 || rhs.0)
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        Self(self.0 && rhs.0)
+    }
+}
+
+impl OrderedSemiring for Reachable {
+    fn better(&self, other: &Self) -> bool {
+        self.0 && !other.0
+    }
+}
+
+/// order values by `better`, so the best one is on the top of the (min) heap
+#[derive(Clone, Copy)]
+struct Best<S>(S);
+
+impl<S: OrderedSemiring> PartialEq for Best<S> {
+    fn eq(&self, other: &Self) -> bool {
+        !self.0.better(&other.0) && !other.0.better(&self.0)
+    }
+}
+
+impl<S: OrderedSemiring> PartialOrd for Best<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.0.better(&other.0) {
+            Some(Ordering::Less)
+        } else if other.0.better(&self.0) {
+            Some(Ordering::Greater)
+        } else {
+            Some(Ordering::Equal)
+        }
+    }
+}
+
+/// an edge better than the empty path (e.g. a negative weight) may make a path better,
+/// so a path overflowed by it is recorded as overflowed downward
+fn record_overflow<S: Semiring>(tracker: &mut OverflowTracker, v: usize, e: S) {
+    tracker.record(v, e.plus(S::one()) != S::one());
+}
+
+/// `value` plus a new path `cand`, None means no path yet
+fn plus_path<S: Semiring>(value: Option<S>, cand: S) -> S {
+    match value {
+        Some(x) => x.plus(cand),
+        None => cand,
+    }
+}
+
+/// dijkstra over an ordered semiring
+/// return (value, from), value[u] is S::zero() if u is unreachable, from[u] is one best predecessor
+pub fn semiring_dijkstra<G, S, F>(graph: &G, start: impl Into<VertexId>, edge: F) -> (Vec<S>, Vec<VertexId>)
+where
+    G: Graph,
+    S: OrderedSemiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    let mut iter = SemiringDijkstraIter::new(graph, start, edge);
+    iter.by_ref().for_each(drop);
+    let value = iter.value.into_iter().map(|x| x.unwrap_or_else(S::zero)).collect();
+    (value, vertex_ids(iter.from))
+}
+
+/// yield PathEdge(from, to, value) of every reachable vertex, the best value first
+/// every vertex is in the heap at most once (decrease_key rather than lazy deletion)
+pub struct SemiringDijkstraIter<'a, G: Graph, S: OrderedSemiring, F> {
+    graph: &'a G,
+    edge: F,
+    used: Vec<bool>,
+    value: Vec<Option<S>>, // None: no path found yet
+    from: Vec<G::Index>,
+    heap: IndexedHeap<(Best<S>, G::Index), G::Index>, // (value, vertex), the vertex is used to break ties
+    tracker: OverflowTracker,
+}
+
+impl<'a, G, S, F> SemiringDijkstraIter<'a, G, S, F>
+where
+    G: Graph,
+    S: OrderedSemiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    pub fn new(graph: &'a G, start: impl Into<VertexId>, edge: F) -> Self {
+        Self::with_arity(graph, start, edge, 2)
+    }
+
+    /// use a d-ary heap, which is faster on dense graph (more decrease_key than pop)
+    pub fn with_arity(graph: &'a G, start: impl Into<VertexId>, edge: F, arity: usize) -> Self {
+        let start = start.into().index();
+        let n = graph.len();
+        let mut used = vec![false; n];
+        used[start] = true;
+        let mut value = vec![None; n];
+        value[start] = Some(S::one());
+        let mut res = Self {
+            graph,
+            edge,
+            used,
+            value,
+            from: vec![G::Index::new(start); n],
+            heap: IndexedHeap::with_index(n, arity),
+            tracker: OverflowTracker::new(n),
+        };
+        res.relax(start, S::one());
+        res
+    }
+
+    fn relax(&mut self, u: usize, value_u: S) {
+        for (v, w) in self.graph.iter_e_from(u) {
+            if self.used[v] {
+                continue;
+            }
+            let e = (self.edge)(u, v, w);
+            match value_u.checked_times(e) {
+                Some(cand) => {
+                    if self.heap.decrease_key(v, (Best(cand), G::Index::new(v))) {
+                        self.from[v] = G::Index::new(u);
+                    }
+                    // not idempotent (Counting) if it ties
+                    self.value[v] = Some(plus_path(self.value[v], cand));
+                }
+                None => record_overflow(&mut self.tracker, v, e),
+            }
+        }
+    }
+
+    /// some vertex is reached only by overflowed paths,
+    /// it's only meaningful after all the vertices are yielded
+    pub fn overflowed(&self) -> bool {
+        self.tracker.overflowed(|v| self.used[v])
+    }
+}
+
+impl<'a, G, S, F> Iterator for SemiringDijkstraIter<'a, G, S, F>
+where
+    G: Graph,
+    S: OrderedSemiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    type Item = PathEdge<S>;
+
+    fn next(&mut self) -> Option<PathEdge<S>> {
+        let (u, _) = self.heap.pop()?;
+        self.used[u] = true;
+        let value_u = self.value[u].unwrap();
+        self.relax(u, value_u);
+        Some(PathEdge::new(self.from[u].index(), u, value_u))
+    }
+}
+
+/// bellman ford over a semiring whose plus is idempotent (not Counting)
+/// return (converged?, value, from), not converged means some cycle keep improving the value
+/// (e.g. negative cycle of MinPlus)
+pub fn semiring_bellman_ford<G, S, F>(graph: &G, start: impl Into<VertexId>, edge: F) -> (bool, Vec<S>, Vec<VertexId>)
+where
+    G: Graph,
+    S: Semiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    let start = start.into().index();
+    let (state, value, from, _overflowed) = solve_bellman_ford(graph, start, edge);
+    (state, value.into_iter().map(|x| x.unwrap_or_else(S::zero)).collect(), from)
+}
+
+/// return (converged?, value, from, overflowed?), value[u] is None if u is unreachable
+#[allow(clippy::type_complexity)]
+pub(crate) fn solve_bellman_ford<G, S, F>(
+    graph: &G,
+    start: usize,
+    edge: F,
+) -> (bool, Vec<Option<S>>, Vec<VertexId>, bool)
+where
+    G: Graph,
+    S: Semiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    let n = graph.len();
+    let mut value = vec![None; n];
+    let mut from = vec![G::Index::new(start); n];
+    value[start] = Some(S::one());
+    let mut tracker = OverflowTracker::new(n);
+
+    let mut state = false;
+    for _ in 0..n {
+        let mut improved = false;
+        for u in 0..n {
+            let value_u = match value[u] {
+                Some(x) => x,
+                None => continue,
+            };
+            for (v, w) in graph.iter_e_from(u) {
+                let e = edge(u, v, w);
+                match value_u.checked_times(e) {
+                    Some(cand) => {
+                        let new = Some(plus_path(value[v], cand));
+                        if new != value[v] {
+                            from[v] = G::Index::new(u);
+                            value[v] = new;
+                            improved = true;
+                        }
+                    }
+                    None => record_overflow(&mut tracker, v, e),
+                }
+            }
+        }
+        if !improved {
+            state = true;
+            break;
+        }
+    }
+    let overflowed = tracker.overflowed(|v| value[v].is_some());
+    (state, value, vertex_ids(from), overflowed)
+}
+
+/// floyd warshall over a semiring whose plus is idempotent (not Counting)
+pub fn semiring_floyd_warshall<G, S, F>(graph: &G, edge: F) -> Vec<Vec<S>>
+where
+    G: Graph,
+    S: Semiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    let (value, _overflowed) = solve_floyd_warshall(graph, edge);
+    value
+        .into_iter()
+        .map(|value_u| value_u.into_iter().map(|x| x.unwrap_or_else(S::zero)).collect())
+        .collect()
+}
+
+/// return (value, overflowed?), value[u][v] is None if there is no path u -> v
+#[allow(clippy::type_complexity)]
+pub(crate) fn solve_floyd_warshall<G, S, F>(graph: &G, edge: F) -> (Vec<Vec<Option<S>>>, bool)
+where
+    G: Graph,
+    S: Semiring,
+    F: Fn(usize, usize, G::Weight) -> S,
+{
+    let n = graph.len();
+    let mut value = vec![vec![None; n]; n];
+    for (u, value_u) in value.iter_mut().enumerate() {
+        value_u[u] = Some(S::one());
+        for (v, w) in graph.iter_e_from(u) {
+            value_u[v] = Some(plus_path(value_u[v], edge(u, v, w)));
+        }
+    }
+    let mut trackers: Vec<_> = (0..n).map(|_| OverflowTracker::new(n)).collect();
+    for i in 0..n {
+        for u in 0..n {
+            if u == i {
+                continue;
+            }
+            for v in 0..n {
+                // value[u][i] may be changed when v == i, read it every time
+                if let (Some(a), Some(b)) = (value[u][i], value[i][v]) {
+                    match a.checked_times(b) {
+                        Some(cand) => value[u][v] = Some(plus_path(value[u][v], cand)),
+                        None => record_overflow(&mut trackers[u], v, b),
+                    }
+                }
+            }
+        }
+    }
+    let overflowed = overflowed_rows(&trackers, &value);
+    (value, overflowed)
+}
+
+/// the capacity of the widest path from `start` to every vertex (zero if unreachable)
+pub fn widest_path<G: Graph>(graph: &G, start: impl Into<VertexId>) -> (Vec<G::Weight>, Vec<VertexId>) {
+    let start = start.into().index();
+    let (value, from) = semiring_dijkstra(graph, start, |_, _, w| Widest(w));
+    (value.into_iter().map(|x| x.0).collect(), from)
+}
+
+/// the number of shortest paths from `start` to every vertex (zero if unreachable)
+/// NOTE: all weights should be positive
+pub fn count_shortest_paths<G: Graph>(graph: &G, start: impl Into<VertexId>) -> Vec<u64> {
+    let start = start.into().index();
+    let (value, _) = semiring_dijkstra(graph, start, |_, _, w| Counting::new(w));
+    value.into_iter().map(|x| x.count).collect()
+}
+
+/// all the vertices which can be reached from `start`
+pub fn reachable<G: Graph>(graph: &G, start: impl Into<VertexId>) -> Vec<bool> {
+    let start = start.into().index();
+    let (value, _) = semiring_dijkstra(graph, start, |_, _, _| Reachable(true));
+    value.into_iter().map(|x| x.0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MakeGraph, VGraph};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_semiring() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+
+        // MinPlus is the same as dijkstra/bellman_ford/floyd_warshall
+        let (value, _) = semiring_dijkstra(&g, 7, |_, _, w| MinPlus(w));
+        let dist: Vec<Option<i32>> = g.bellman_ford(7).1;
+        assert_eq!(value.iter().map(|x| Some(x.0)).collect::<Vec<_>>(), dist);
+        let (state, value2, _) = semiring_bellman_ford(&g, 7, |_, _, w| MinPlus(w));
+        assert!(state);
+        assert_eq!(value, value2);
+        let all = semiring_floyd_warshall(&g, |_, _, w| MinPlus(w));
+        assert_eq!(all[7], value);
+
+        // a -> b -> c (4, 8) is shorter, but a -> h -> b -> c (8, 11, 8) is wider
+        let (cap, from) = widest_path(&g, 0);
+        assert_eq!(cap[2], 8);
+        assert_eq!((from[2], from[1], from[7]), (VertexId(1), VertexId(7), VertexId(0)));
+        let all = semiring_floyd_warshall(&g, |_, _, w| Widest(w));
+        assert_eq!(all[0].iter().map(|x| x.0).collect::<Vec<_>>(), cap[..]);
+
+        let p = |w: i32| Reliability::new(1.0 / w as f64).unwrap();
+        let (value, _) = semiring_dijkstra(&g, 0, |_, _, w| p(w));
+        let (_, value2, _) = semiring_bellman_ford(&g, 0, |_, _, w| p(w));
+        assert_eq!(value[1].get(), 0.25);
+        assert_eq!(value, value2);
+        assert!(Reliability::new(1.5).is_err());
+
+        assert!(reachable(&g, 0).into_iter().all(|x| x));
+        let (g, _s_lst) = MakeGraph::scc();
+        let lhs = reachable(&g, 4);
+        let rhs = semiring_floyd_warshall(&g, |_, _, _| Reachable(true));
+        assert_eq!(lhs, rhs[4].iter().map(|x| x.0).collect::<Vec<_>>());
+        assert!(!lhs[0]);
+    }
+
+    #[test]
+    fn test_count_shortest_paths() {
+        // a diamond: 0 -> 1 -> 3, 0 -> 2 -> 3, then 3 -> 4 and a longer 0 -> 4
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 1), (2, 1), (4, 5)]),
+            HashMap::from([(3, 1)]),
+            HashMap::from([(3, 1)]),
+            HashMap::from([(4, 1)]),
+            HashMap::new(),
+        ]);
+        assert_eq!(count_shortest_paths(&g, 0), vec![1, 1, 1, 2, 2]);
+        let g = VGraph::new(vec![HashMap::from([(1, 1)]), HashMap::new(), HashMap::new()]);
+        assert_eq!(count_shortest_paths(&g, 0), vec![1, 1, 0]);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::Graph;
+use crate::view::MapWeight;
+use crate::index::vertex_ids;
+use crate::weight::OverflowTracker;
+use crate::{Graph, GraphError, IndexType, VertexId, Weight};
 use std::collections::VecDeque;
 
-const SENTINEL: usize = usize::MAX;
-
 /// Shortest Path Faster Algorithm
 /// prove the correctness of the queue implemention (not stack):
 /// 1. every time, in the queue, we can only find two kinds of vertices:
@@ -16,52 +17,113 @@ const SENTINEL: usize = usize::MAX;
 ///    2: push its one neighbour `v` to the end of the queue (give it a level i+1 distance, after all level i vertex outqueue, we can get the shortest level i+1 distance)
 ///    3: update its one neighbour `v` after sentinel, (give it another level i+1 distance)
 ///    4: update its one neighbour `v` before sentinel (v get a level i+1 distance, after v outqueue, we may achieve more deeper level)
+///
 /// NOTE: if we use priorityqueue (distance), sentinel will not work
+///
+/// return (no negative cycle?, dist, from), the same as bellman_ford
+/// NOTE: overflowed candidates are ignored (see bellman_ford), use try_spfa to get an error instead
+pub fn spfa<G: Graph>(graph: &G, start: impl Into<VertexId>) -> (bool, Vec<Option<G::Weight>>, Vec<VertexId>) {
+    let start = start.into().index();
+    let (state, dist, from, _overflowed) = spfa_checked(graph, start);
+    (state, dist, from)
+}
+
+/// return Err if `start` is out of range, negative cycle existed or some distance overflowed
+#[allow(clippy::type_complexity)]
+pub fn try_spfa<G: Graph>(
+    graph: &G,
+    start: impl Into<VertexId>,
+) -> Result<(Vec<Option<G::Weight>>, Vec<VertexId>), GraphError> {
+    let start = start.into().index();
+    graph.check_vertex(start)?;
+    match spfa_checked(graph, start) {
+        (false, _, _, _) => Err(GraphError::NegativeCycle),
+        (true, _, _, true) => Err(GraphError::Overflow),
+        (true, dist, from, false) => Ok((dist, from)),
+    }
+}
+
+/// distances are accumulated in D rather than G::Weight (see dijkstra_as)
+pub fn spfa_as<G, D>(graph: &G, start: impl Into<VertexId>) -> (bool, Vec<Option<D>>, Vec<VertexId>)
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    let start = start.into().index();
+    spfa(&MapWeight::new(graph, |_, _, w| D::from(w)), start)
+}
 
-pub fn spfa<G: Graph>(graph: &G, start: usize) -> (bool, Vec<Option<G::Weight>>, Vec<usize>) {
+#[allow(clippy::type_complexity)]
+pub fn try_spfa_as<G, D>(graph: &G, start: impl Into<VertexId>) -> Result<(Vec<Option<D>>, Vec<VertexId>), GraphError>
+where
+    G: Graph,
+    D: Weight + From<G::Weight>,
+{
+    let start = start.into().index();
+    try_spfa(&MapWeight::new(graph, |_, _, w| D::from(w)), start)
+}
+
+/// return (no negative cycle?, dist, from, overflowed?), see OverflowTracker
+#[allow(clippy::type_complexity)]
+fn spfa_checked<G: Graph>(
+    graph: &G,
+    start: usize,
+) -> (bool, Vec<Option<G::Weight>>, Vec<VertexId>, bool) {
     let n = graph.len();
     let mut dist = vec![None; n];
     dist[start] = Some(Default::default());
-    let mut from = vec![start; n];
+    let mut from = vec![G::Index::new(start); n];
     let mut is_in_queue = vec![false; n];
+    let mut tracker = OverflowTracker::new(n);
+    let mut state = true;
 
+    // G::Index::end() is the sentinel
     let mut queue = VecDeque::new();
-    queue.push_back(start);
-    queue.push_back(SENTINEL);
+    queue.push_back(G::Index::new(start));
+    queue.push_back(G::Index::end());
 
     let mut level = 0;
     while let Some(u) = queue.pop_front() {
-        if u == SENTINEL {
+        if u == G::Index::end() {
             if queue.is_empty() {
                 break;
             } else {
                 level += 1;
                 if level == n {
-                    panic!("Negative cycle existed!");
+                    state = false; // negative cycle existed
+                    break;
                 }
                 queue.push_back(u);
             }
         } else {
+            let u = u.index();
             is_in_queue[u] = false;
             let du = dist[u].unwrap();
             for (v, w) in graph.iter_e_from(u) {
-                if dist[v].is_none() || du + w < dist[v].unwrap() {
-                    from[v] = u;
-                    dist[v] = Some(du + w);
+                let d = match tracker.add(du, w, v) {
+                    Some(d) => d,
+                    None => continue,
+                };
+                if dist[v].is_none() || d < dist[v].unwrap() {
+                    from[v] = G::Index::new(u);
+                    dist[v] = Some(d);
                     if !is_in_queue[v] {
-                        queue.push_back(v);
+                        queue.push_back(G::Index::new(v));
                         is_in_queue[v] = true;
                     }
                 }
             }
         }
     }
-    (true, dist, from)
+    let overflowed = tracker.overflowed(|v| dist[v].is_some());
+    (state, dist, vertex_ids(from), overflowed)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::MakeGraph;
+    use super::*;
+    use crate::{MakeGraph, VGraph};
+    use std::collections::HashMap;
 
     #[test]
     fn test_spfa() {
@@ -74,8 +136,41 @@ mod tests {
         for i in 0..dist.len() {
             println!(
                 "to: {}, directly from: {}, distance: {:?}",
-                s_lst[i], s_lst[from[i]], dist[i]
+                s_lst[i], s_lst[from[i].index()], dist[i]
             )
         }
     }
+
+    #[test]
+    fn test_try_spfa() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let (dist, _from) = try_spfa(&g, 7).unwrap();
+        assert_eq!(dist, g.bellman_ford(7).1);
+        assert_eq!(
+            try_spfa(&g, 9),
+            Err(GraphError::VertexOutOfRange { index: 9, len: 9 })
+        );
+
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 1)]),
+            HashMap::from([(2, -3)]),
+            HashMap::from([(1, 1)]),
+        ]);
+        assert_eq!(try_spfa(&g, 0), Err(GraphError::NegativeCycle));
+
+        let g = VGraph::new(vec![HashMap::from([(1, i16::MAX)]), HashMap::from([(2, 1)]), HashMap::new()]);
+        assert_eq!(try_spfa(&g, 0), Err(GraphError::Overflow));
+        assert_eq!(spfa(&g, 0).1[2], None);
+        assert_eq!(try_spfa_as::<_, i32>(&g, 0).unwrap().0[2], Some(i16::MAX as i32 + 1));
+        assert_eq!(spfa_as::<_, i64>(&g, 0).1[2], Some(i16::MAX as i64 + 1));
+
+        // 0 -> 1 -> 3 overflowed, but it's not the shortest path 0 -> 2 -> 1 -> 3
+        let g = VGraph::new(vec![
+            HashMap::from([(1, 200u8), (2, 10)]),
+            HashMap::from([(3, 100)]),
+            HashMap::from([(1, 10)]),
+            HashMap::new(),
+        ]);
+        assert_eq!(try_spfa(&g, 0).unwrap().0, vec![Some(0), Some(20), Some(10), Some(120)]);
+    }
 }
@@ -1,6 +1,6 @@
 /// this module provide some testing graphs for doing experiment
 use crate::{make_symbol_lst, make_vertices, make_vertices_rec};
-use crate::{NoWeight, VGraph};//, EGraph, FlowEdge, CostFlowEdge};
+use crate::{Direction, EGraph, FlowEdge, Graph, GraphBuilder, NoWeight, UGraph, VGraph};
 use std::collections::HashMap;
 
 pub struct MakeGraph;
@@ -11,8 +11,7 @@ impl MakeGraph {
     pub fn scc() -> (VGraph<NoWeight>, Vec<&'static str>) {
         make_vertices!(a, b, c, d, e, f, g, h, i);
         let s_lst = make_symbol_lst!(a, b, c, d, e, f, g, h, i);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v) in [
+        let edges = [
             (a, b),
             (a, c),
             (b, d),
@@ -27,12 +26,12 @@ impl MakeGraph {
             (g, h),
             (h, i),
             (i, h),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, NoWeight);
-        }
-        (VGraph::new(lst), s_lst)
+        ];
+        let g = GraphBuilder::new(s_lst.len())
+            .edges(edges.map(|(u, v)| (u, v, NoWeight)))
+            .build_vgraph()
+            .unwrap();
+        (g, s_lst)
     }
 
     /// Minimum Spanning Tree
@@ -40,8 +39,7 @@ impl MakeGraph {
     pub fn mst(add_rev: bool) -> (VGraph<i32>, Vec<&'static str>) {
         make_vertices!(a, b, c, d, e, f, g, h, i);
         let s_lst = make_symbol_lst!(a, b, c, d, e, f, g, h, i);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v, w) in [
+        let edges = [
             (a, b, 4),
             (a, h, 8),
             (b, c, 8),
@@ -56,23 +54,27 @@ impl MakeGraph {
             (g, h, 1),
             (g, i, 6),
             (h, i, 7),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, w);
-            if add_rev {
-                lst[v].insert(u, w);
-            }
-        }
-        (VGraph::new(lst), s_lst)
+        ];
+        let direction = if add_rev { Direction::Both } else { Direction::Forward };
+        let g = GraphBuilder::new(s_lst.len())
+            .edges(edges)
+            .direction(direction)
+            .build_vgraph()
+            .unwrap();
+        (g, s_lst)
+    }
+
+    /// the same graph as mst, but undirected
+    pub fn mst_undirected() -> (UGraph<i32>, Vec<&'static str>) {
+        let (g, s_lst) = Self::mst(false);
+        (UGraph::from_graph(&g), s_lst)
     }
 
     /// Graph carry additional data (for example: x, y coordinate)
     pub fn spa() -> (VGraph<f64>, Vec<&'static str>, Vec<(f64, f64)>) {
         make_vertices!(s, a, b, c, d, e, t);
         let s_lst = make_symbol_lst!(s, a, b, c, d, e, t);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v, w) in [
+        let edges = [
             (s, a, 3.0),
             (s, d, 2.0),
             (a, b, 2.0),
@@ -80,12 +82,12 @@ impl MakeGraph {
             (c, t, 3.0),
             (d, e, 4.0),
             (e, t, 4.5),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, w);
-            lst[v].insert(u, w); // always need backward edges
-        }
+        ];
+        let g = GraphBuilder::new(s_lst.len())
+            .edges(edges)
+            .direction(Direction::Both) // always need backward edges
+            .build_vgraph()
+            .unwrap();
 
         let dx = 3f64.powf(0.5);
         let xy = vec![
@@ -98,7 +100,7 @@ impl MakeGraph {
             (1.0 + 3.0 * dx, 3.0),
         ];
 
-        (VGraph::new(lst), s_lst, xy)
+        (g, s_lst, xy)
     }
 
     /// Shortest Path with Negative weight edge
@@ -106,8 +108,7 @@ impl MakeGraph {
     pub fn spn() -> (VGraph<i32>, Vec<&'static str>) {
         make_vertices!(v1, v2, v3, v4, v5);
         let s_lst = make_symbol_lst!(v1, v2, v3, v4, v5);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v, w) in [
+        let edges = [
             (v1, v2, 3),
             (v1, v3, 8),
             (v1, v5, -4),
@@ -117,20 +118,16 @@ impl MakeGraph {
             (v4, v1, 2),
             (v4, v3, -5),
             (v5, v4, 6),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, w);
-        }
-        (VGraph::new(lst), s_lst)
+        ];
+        let g = GraphBuilder::new(s_lst.len()).edges(edges).build_vgraph().unwrap();
+        (g, s_lst)
     }
 
     /// Maximum Bipartite Matching
     pub fn mbm() -> (VGraph<NoWeight>, Vec<&'static str>) {
         make_vertices!(x1, x2, x3, x4, x5, x6, y1, y2, y3, y4, y5, y6);
         let s_lst = make_symbol_lst!(x1, x2, x3, x4, x5, x6, y1, y2, y3, y4, y5, y6);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v) in [
+        let edges = [
             (x1, y1),
             (x1, y4),
             (x2, y1),
@@ -142,12 +139,12 @@ impl MakeGraph {
             (x4, y3),
             (x5, y6),
             (x6, y5),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, NoWeight);
-        }
-        (VGraph::new(lst), s_lst)
+        ];
+        let g = GraphBuilder::new(s_lst.len())
+            .edges(edges.map(|(u, v)| (u, v, NoWeight)))
+            .build_vgraph()
+            .unwrap();
+        (g, s_lst)
     }
 
     /// Disjoint Path
@@ -155,8 +152,7 @@ impl MakeGraph {
     pub fn dp() -> (VGraph<NoWeight>, Vec<&'static str>) {
         make_vertices!(s, a, b, c, d, e, t);
         let s_lst = make_symbol_lst!(s, a, b, c, d, e, t);
-        let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v) in [
+        let edges = [
             (s, a),
             (s, c),
             (s, e),
@@ -167,20 +163,19 @@ impl MakeGraph {
             (c, t),
             (d, t),
             (e, c),
-        ]
-        .into_iter()
-        {
-            lst[u].insert(v, NoWeight);
-        }
-        (VGraph::new(lst), s_lst)
+        ];
+        let g = GraphBuilder::new(s_lst.len())
+            .edges(edges.map(|(u, v)| (u, v, NoWeight)))
+            .build_vgraph()
+            .unwrap();
+        (g, s_lst)
     }
 
     pub fn mf() -> (VGraph<i32>, Vec<&'static str>) {
         make_vertices!(s, v1, v2, v3, v4, t);
-	let s_lst = make_symbol_lst!(s, v1, v2, v3, v4, t);
-	let mut lst = vec![HashMap::new(); s_lst.len()];
-        for (u, v, w) in [
-	    (s, v1, 16),
+        let s_lst = make_symbol_lst!(s, v1, v2, v3, v4, t);
+        let edges = [
+            (s, v1, 16),
             (s, v2, 13),
             (v1, v3, 12),
             (v2, v1, 4),
@@ -188,20 +183,25 @@ impl MakeGraph {
             (v3, v2, 9),
             (v3, t, 20),
             (v4, v3, 7),
-            (v4, t, 4)]
-	    .into_iter() {
-		lst[u].insert(v, w);
-	    }
-	(VGraph::new(lst), s_lst)
+            (v4, t, 4),
+        ];
+        let g = GraphBuilder::new(s_lst.len()).edges(edges).build_vgraph().unwrap();
+        (g, s_lst)
+    }
+
+    /// the same graph as mf, but every edge is a FlowEdge
+    pub fn mf_egraph() -> (EGraph<i32, FlowEdge<i32>>, Vec<&'static str>) {
+        let (g, s_lst) = Self::mf();
+        let e_lst = g.iter_e_all().map(|(u, v, w)| FlowEdge::new(u, v, w)).collect();
+        (EGraph::new(e_lst, g.len()), s_lst)
     }
 
     #[allow(clippy::type_complexity)]
     pub fn mcmf() -> (VGraph<i32>, Vec<&'static str>, HashMap<(usize, usize), i32>) {
         make_vertices!(s, v1, v2, v3, v4, t);
-	let s_lst = make_symbol_lst!(s, v1, v2, v3, v4, t);
-	let mut lst = vec![HashMap::new(); s_lst.len()]; // cost graph
-        for (u, v, w) in [
-	    (s, v1, 3), // change 1 <-> 3 see what happened
+        let s_lst = make_symbol_lst!(s, v1, v2, v3, v4, t);
+        let edges = [
+            (s, v1, 3), // change 1 <-> 3 see what happened
             (s, v2, 1),
             (v1, v3, 1),
             (v2, v1, 1),
@@ -209,10 +209,9 @@ impl MakeGraph {
             (v3, v2, 1),
             (v3, t, 1),
             (v4, v3, 1),
-            (v4, t, 1)]
-	    .into_iter() {
-		lst[u].insert(v, w);
-	    }
+            (v4, t, 1),
+        ];
+        let g = GraphBuilder::new(s_lst.len()).edges(edges).build_vgraph().unwrap(); // cost graph
 	let mut cap_dct = HashMap::new();
 	for (u, v, w) in [
 	    (s, v1, 16),
@@ -227,6 +226,6 @@ impl MakeGraph {
 	    .into_iter() {
 		cap_dct.insert((u, v), w);
 	    }
-	(VGraph::new(lst), s_lst, cap_dct)
+	(g, s_lst, cap_dct)
     }
 }
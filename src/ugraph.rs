@@ -0,0 +1,204 @@
+/// undirected graph, every edge u - v is stored only once,
+/// and exposed in both directions (u -> v and v -> u) through the Graph trait,
+/// so algorithms which need symmetric edges (such as prim) can always get them
+use crate::error::check_vertex;
+use crate::{Graph, GraphError, VertexId, Weight};
+use std::collections::{hash_map, HashMap};
+use std::iter::Copied;
+
+pub struct UGraph<W: Weight> {
+    e_lst: Vec<(usize, usize, W)>,   // all edges, (u, v, w) with u <= v
+    adj: Vec<HashMap<usize, usize>>, // ith HashMap: ith vertex's neighbour -> edge id
+}
+
+impl<W: Weight> UGraph<W> {
+    /// make a graph of `n` isolated vertices
+    pub fn new(n: usize) -> Self {
+        Self {
+            e_lst: vec![],
+            adj: vec![HashMap::new(); n],
+        }
+    }
+
+    pub fn from_edges<I>(n: usize, edges: I) -> Result<Self, GraphError>
+    where
+        I: IntoIterator<Item = (usize, usize, W)>,
+    {
+        let mut graph = Self::new(n);
+        for (u, v, w) in edges {
+            graph.add_edge(u, v, w)?;
+        }
+        Ok(graph)
+    }
+
+    /// take every directed edge of `graph` as an undirected one,
+    /// NOTE: if both u -> v and v -> u existed, only one of their weights will be kept
+    pub fn from_graph<G: Graph<Weight = W>>(graph: &G) -> Self {
+        let mut res = Self::new(graph.len());
+        for (u, v, w) in graph.iter_e_all() {
+            res.add_edge(u, v, w).unwrap(); // it's ok to unwrap, since u, v < n
+        }
+        res
+    }
+
+    /// add an isolated vertex, return its index
+    pub fn add_vertex(&mut self) -> usize {
+        self.adj.push(HashMap::new());
+        self.adj.len() - 1
+    }
+
+    /// add edge u - v, return the old weight if the edge already existed
+    pub fn add_edge(&mut self, u: usize, v: usize, w: W) -> Result<Option<W>, GraphError> {
+        check_vertex(u, self.len())?;
+        check_vertex(v, self.len())?;
+        if let Some(&i) = self.adj[u].get(&v) {
+            Ok(Some(std::mem::replace(&mut self.e_lst[i].2, w)))
+        } else {
+            let i = self.e_lst.len();
+            let (a, b) = if u > v { (v, u) } else { (u, v) };
+            self.e_lst.push((a, b, w));
+            self.adj[u].insert(v, i);
+            self.adj[v].insert(u, i);
+            Ok(None)
+        }
+    }
+
+    /// iter every undirected edge only once: (u, v, w) with u <= v
+    pub fn iter_undirected_edges(&self) -> impl Iterator<Item = (usize, usize, W)> + '_ {
+        self.e_lst.iter().cloned()
+    }
+}
+
+/// a vertex's (neighbour, weight), weight is looked up by the edge id
+pub struct UGraphIter<'a, W> {
+    adj: hash_map::Iter<'a, usize, usize>,
+    e_lst: &'a [(usize, usize, W)],
+}
+
+impl<'a, W: Copy> Iterator for UGraphIter<'a, W> {
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        self.adj.next().map(|(v, i)| (*v, self.e_lst[*i].2))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.adj.size_hint()
+    }
+}
+
+impl<W: Weight> Graph for UGraph<W> {
+    type Weight = W;
+    type Index = usize;
+    type IterVFrom<'a> = Copied<hash_map::Keys<'a, usize, usize>> where Self: 'a;
+    type IterVTo<'a> = Copied<hash_map::Keys<'a, usize, usize>> where Self: 'a;
+    type IterEFrom<'a> = UGraphIter<'a, W> where Self: 'a;
+    type IterETo<'a> = UGraphIter<'a, W> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        self.adj[u].keys().copied()
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.adj[u].is_empty()
+    }
+
+    /// the same as iter_v_from
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        self.iter_v_from(u)
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        self.adj[u].is_empty()
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        UGraphIter {
+            adj: self.adj[u].iter(),
+            e_lst: &self.e_lst,
+        }
+    }
+
+    /// the same as iter_e_from
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        self.iter_e_from(u)
+    }
+
+    fn out_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.adj[u].len()
+    }
+
+    fn in_degree(&self, u: impl Into<VertexId>) -> usize {
+        let u = u.into().index();
+        self.adj[u].len()
+    }
+
+    fn has_edge(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> bool {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.adj[u].contains_key(&v)
+    }
+
+    fn weight(&self, u: impl Into<VertexId>, v: impl Into<VertexId>) -> Option<W> {
+        let u = u.into().index();
+        let v = v.into().index();
+        self.adj[u].get(&v).map(|&i| self.e_lst[i].2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MakeGraph, TreeEdge};
+
+    #[test]
+    fn test_ugraph() {
+        let (g, s_lst) = MakeGraph::mst_undirected();
+        assert_eq!(g.iter_undirected_edges().count(), 14);
+        for u in 0..g.len() {
+            for (v, w) in g.iter_e_from(u) {
+                assert!(g.iter_e_from(v).any(|e| e == (u, w)));
+            }
+        }
+
+        let res = g.kruskal();
+        assert_eq!(res.len(), 8);
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37);
+
+        let res = g.prim();
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37);
+        for TreeEdge { from, to, weight } in res.into_iter() {
+            println!("weight: {}, from: {}, to: {}", weight, s_lst[from.index()], s_lst[to.index()]);
+        }
+    }
+
+    #[test]
+    fn test_ugraph_add_edge() {
+        let mut g = UGraph::new(2);
+        assert_eq!(g.add_edge(1, 0, 3), Ok(None));
+        assert_eq!(g.add_edge(0, 1, 4), Ok(Some(3)));
+        assert_eq!(g.iter_undirected_edges().collect::<Vec<_>>(), vec![(0, 1, 4)]);
+        assert_eq!(
+            g.add_edge(0, 2, 1),
+            Err(GraphError::VertexOutOfRange { index: 2, len: 2 })
+        );
+        let c = g.add_vertex();
+        assert_eq!(g.add_edge(c, c, 1), Ok(None));
+        assert_eq!(g.iter_e_from(c).collect::<Vec<_>>(), vec![(c, 1)]);
+    }
+}
@@ -11,6 +11,7 @@ use std::rc::Rc;
 /// just give it a zst: () for example
 /// it's helpful sometimes, see a_star.rs
 // #[derive(Clone)] // T can be any type, don't need a Clone bound
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawVertex<T> {
     label: String,
     index: usize,
@@ -66,9 +67,9 @@ impl<T> Vertex<T> {
         self.borrow_mut().index = index;
     }
 
-    // pub(crate) fn get_label(&self) -> String {
-    //     self.borrow().label.clone()
-    // }
+    pub fn get_label(&self) -> String {
+        self.borrow().label.clone()
+    }
 }
 
 impl<T: Default> Vertex<T> {
@@ -108,6 +109,56 @@ impl<T> PartialEq for Vertex<T> {
     }
 }
 
+/// the same as RawVertex, a deserialized Vertex is not shared with anyone
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Vertex<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.borrow().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Vertex<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawVertex::deserialize(deserializer)?;
+        Ok(Self(Rc::new(RefCell::new(raw))))
+    }
+}
+
+/// index of a vertex in a graph, so it can not be mixed up with other usizes
+/// vertex parameters (of the Graph trait, every algorithm and result constructor)
+/// are impl Into<VertexId>, so a bare usize still works,
+/// while returned vertices are typed: TreeEdge, PathEdge, ShortestPathTree and the `from` vectors
+/// NOTE: the vertices yielded by Graph's iterators and per-vertex results indexed by vertex
+/// (dist, matchings, scc) are still plain usize
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexId(pub usize);
+
+impl VertexId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for VertexId {
+    fn from(u: usize) -> Self {
+        Self(u)
+    }
+}
+
+impl From<VertexId> for usize {
+    fn from(u: VertexId) -> Self {
+        u.0
+    }
+}
+
+impl fmt::Display for VertexId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,4 @@
-use crate::Graph;
+use crate::{Graph, VertexId};
 use std::collections::HashMap;
 
 /// you can get the count of vertex disjoint path using: last_but_t.len()
@@ -7,9 +7,11 @@ use std::collections::HashMap;
 /// 2. matching keeps all sub paths (backward, (k, v) in it means edge v -> k)
 pub fn vertex_disjoint_path<G: Graph>(
     graph: &G,
-    start: usize,
-    target: usize,
+    start: impl Into<VertexId>,
+    target: impl Into<VertexId>,
 ) -> (HashMap<usize, usize>, Vec<usize>) {
+    let start = start.into().index();
+    let target = target.into().index();
     let mut matching = HashMap::new();
     let mut last_but_t = vec![];
     while vertex_disjoint_augment(graph, &mut matching, &mut last_but_t, start, target) {}
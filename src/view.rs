@@ -0,0 +1,348 @@
+/// zero-copy adaptors over a borrowed graph, all of them implement Graph,
+/// so any algorithm can run on them without copying the graph:
+/// Reversed: every edge u -> v become v -> u
+/// Filtered: only keep the vertices/edges which satisfy the predicates
+/// MapWeight: transform the weight of every edge on the fly
+use crate::graph::Vertices;
+use crate::{Graph, VertexId};
+
+pub struct Reversed<'a, G: Graph> {
+    graph: &'a G,
+}
+
+impl<'a, G: Graph> Reversed<'a, G> {
+    pub fn new(graph: &'a G) -> Self {
+        Self { graph }
+    }
+}
+
+impl<'a, G: Graph> Graph for Reversed<'a, G> {
+    type Weight = G::Weight;
+    type Index = G::Index;
+    type IterVFrom<'b> = G::IterVTo<'b> where Self: 'b;
+    type IterVTo<'b> = G::IterVFrom<'b> where Self: 'b;
+    type IterEFrom<'b> = G::IterETo<'b> where Self: 'b;
+    type IterETo<'b> = G::IterEFrom<'b> where Self: 'b;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        self.graph.iter_v_to(u)
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_to(u)
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        self.graph.iter_v_from(u)
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        true
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_from(u)
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        self.graph.iter_e_to(u)
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        self.graph.iter_e_from(u)
+    }
+}
+
+/// v_pred(u): keep vertex u or not
+/// e_pred(u, v, w): keep edge u -> v or not
+/// NOTE: removed vertices are still counted by len() (so no re-index),
+/// they just have no edges
+pub struct Filtered<'a, G, VP, EP>
+where
+    G: Graph,
+    VP: Fn(usize) -> bool,
+    EP: Fn(usize, usize, G::Weight) -> bool,
+{
+    graph: &'a G,
+    v_pred: VP,
+    e_pred: EP,
+}
+
+impl<'a, G, VP, EP> Filtered<'a, G, VP, EP>
+where
+    G: Graph,
+    VP: Fn(usize) -> bool,
+    EP: Fn(usize, usize, G::Weight) -> bool,
+{
+    pub fn new(graph: &'a G, v_pred: VP, e_pred: EP) -> Self {
+        Self {
+            graph,
+            v_pred,
+            e_pred,
+        }
+    }
+}
+
+/// induced subgraph: only keep vertices (and edges between them) which satisfy v_pred
+#[allow(clippy::type_complexity)]
+pub fn induced_subgraph<G, VP>(
+    graph: &G,
+    v_pred: VP,
+) -> Filtered<'_, G, VP, fn(usize, usize, G::Weight) -> bool>
+where
+    G: Graph,
+    VP: Fn(usize) -> bool,
+{
+    Filtered::new(graph, v_pred, |_, _, _| true)
+}
+
+/// only keep edges which satisfy e_pred
+#[allow(clippy::type_complexity)]
+pub fn edge_subgraph<G, EP>(graph: &G, e_pred: EP) -> Filtered<'_, G, fn(usize) -> bool, EP>
+where
+    G: Graph,
+    EP: Fn(usize, usize, G::Weight) -> bool,
+{
+    Filtered::new(graph, |_| true, e_pred)
+}
+
+impl<'a, G, VP, EP> Graph for Filtered<'a, G, VP, EP>
+where
+    G: Graph,
+    VP: Fn(usize) -> bool,
+    EP: Fn(usize, usize, G::Weight) -> bool,
+{
+    type Weight = G::Weight;
+    type Index = G::Index;
+    type IterVFrom<'b> = Vertices<FilteredIter<'b, G::IterEFrom<'b>, VP, EP>> where Self: 'b;
+    type IterVTo<'b> = Vertices<FilteredIter<'b, G::IterETo<'b>, VP, EP>> where Self: 'b;
+    type IterEFrom<'b> = FilteredIter<'b, G::IterEFrom<'b>, VP, EP> where Self: 'b;
+    type IterETo<'b> = FilteredIter<'b, G::IterETo<'b>, VP, EP> where Self: 'b;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_from(u))
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        let u = u.into().index();
+        Vertices(self.iter_e_to(u))
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        self.graph.has_fast_iter_to()
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        FilteredIter {
+            iter: self.graph.iter_e_from(u),
+            v_pred: &self.v_pred,
+            e_pred: &self.e_pred,
+            u,
+            keep: (self.v_pred)(u),
+            forward: true,
+        }
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        FilteredIter {
+            iter: self.graph.iter_e_to(u),
+            v_pred: &self.v_pred,
+            e_pred: &self.e_pred,
+            u,
+            keep: (self.v_pred)(u),
+            forward: false,
+        }
+    }
+}
+
+/// the edges (v, w) of vertex `u` which satisfy the predicates,
+/// forward: they are u -> v, else v -> u
+pub struct FilteredIter<'a, I, VP, EP> {
+    iter: I,
+    v_pred: &'a VP,
+    e_pred: &'a EP,
+    u: usize,
+    keep: bool,
+    forward: bool,
+}
+
+impl<'a, I, VP, EP, W> Iterator for FilteredIter<'a, I, VP, EP>
+where
+    I: Iterator<Item = (usize, W)>,
+    VP: Fn(usize) -> bool,
+    EP: Fn(usize, usize, W) -> bool,
+    W: Copy,
+{
+    type Item = (usize, W);
+
+    fn next(&mut self) -> Option<(usize, W)> {
+        if !self.keep {
+            return None;
+        }
+        let (u, forward) = (self.u, self.forward);
+        let (v_pred, e_pred) = (self.v_pred, self.e_pred);
+        self.iter.by_ref().find(|&(v, w)| {
+            v_pred(v) && if forward { e_pred(u, v, w) } else { e_pred(v, u, w) }
+        })
+    }
+}
+
+/// func(u, v, w): the new weight of edge u -> v
+pub struct MapWeight<'a, G: Graph, F> {
+    graph: &'a G,
+    func: F,
+}
+
+impl<'a, G, F, W> MapWeight<'a, G, F>
+where
+    G: Graph,
+    F: Fn(usize, usize, G::Weight) -> W,
+{
+    pub fn new(graph: &'a G, func: F) -> Self {
+        Self { graph, func }
+    }
+}
+
+impl<'a, G, F, W> Graph for MapWeight<'a, G, F>
+where
+    G: Graph,
+    F: Fn(usize, usize, G::Weight) -> W,
+    W: crate::Weight,
+{
+    type Weight = W;
+    type Index = G::Index;
+    type IterVFrom<'b> = G::IterVFrom<'b> where Self: 'b;
+    type IterVTo<'b> = G::IterVTo<'b> where Self: 'b;
+    type IterEFrom<'b> = MapWeightIter<'b, G::IterEFrom<'b>, F> where Self: 'b;
+    type IterETo<'b> = MapWeightIter<'b, G::IterETo<'b>, F> where Self: 'b;
+
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    fn iter_v_from(&self, u: impl Into<VertexId>) -> Self::IterVFrom<'_> {
+        self.graph.iter_v_from(u)
+    }
+
+    fn is_empty_from(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_from(u)
+    }
+
+    fn iter_v_to(&self, u: impl Into<VertexId>) -> Self::IterVTo<'_> {
+        self.graph.iter_v_to(u)
+    }
+
+    fn has_fast_iter_to(&self) -> bool {
+        self.graph.has_fast_iter_to()
+    }
+
+    fn is_empty_to(&self, u: impl Into<VertexId>) -> bool {
+        self.graph.is_empty_to(u)
+    }
+
+    fn iter_e_from(&self, u: impl Into<VertexId>) -> Self::IterEFrom<'_> {
+        let u = u.into().index();
+        MapWeightIter {
+            iter: self.graph.iter_e_from(u),
+            func: &self.func,
+            u,
+            forward: true,
+        }
+    }
+
+    fn iter_e_to(&self, u: impl Into<VertexId>) -> Self::IterETo<'_> {
+        let u = u.into().index();
+        MapWeightIter {
+            iter: self.graph.iter_e_to(u),
+            func: &self.func,
+            u,
+            forward: false,
+        }
+    }
+}
+
+/// the edges (v, w) of vertex `u` with new weight,
+/// forward: they are u -> v, else v -> u
+pub struct MapWeightIter<'a, I, F> {
+    iter: I,
+    func: &'a F,
+    u: usize,
+    forward: bool,
+}
+
+impl<'a, I, F, W, W2> Iterator for MapWeightIter<'a, I, F>
+where
+    I: Iterator<Item = (usize, W)>,
+    F: Fn(usize, usize, W) -> W2,
+{
+    type Item = (usize, W2);
+
+    fn next(&mut self) -> Option<(usize, W2)> {
+        let (v, w) = self.iter.next()?;
+        if self.forward {
+            Some((v, (self.func)(self.u, v, w)))
+        } else {
+            Some((v, (self.func)(v, self.u, w)))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bfs, dijkstra, kruskal, MakeGraph};
+
+    #[test]
+    fn test_reversed() {
+        let (g, _s_lst) = MakeGraph::scc();
+        let rev = Reversed::new(&g);
+        for u in 0..g.len() {
+            let mut lhs: Vec<usize> = g.iter_v_to(u).collect();
+            let mut rhs: Vec<usize> = rev.iter_v_from(u).collect();
+            lhs.sort();
+            rhs.sort();
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn test_filtered() {
+        let (g, _s_lst) = MakeGraph::mst(false);
+        // without edge g - h (weight 1), we need to choose f - g (2) and h - i (7) instead
+        let sub = edge_subgraph(&g, |u, v, _w| (u, v) != (6, 7));
+        let res = kruskal::kruskal(&sub);
+        assert_eq!(res.iter().map(|e| e.weight).sum::<i32>(), 37 - 1 + 7);
+
+        let (g, _s_lst) = MakeGraph::scc();
+        // only a, b, c, d (one scc) are kept
+        let sub = induced_subgraph(&g, |u| u < 4);
+        let mut res: Vec<usize> = bfs::bfs(&sub, 0).collect();
+        res.sort();
+        assert_eq!(res, vec![0, 1, 2, 3]);
+        assert!(sub.iter_v_to(7).next().is_none());
+    }
+
+    #[test]
+    fn test_map_weight() {
+        let (g, _s_lst) = MakeGraph::mst(true);
+        let g2 = MapWeight::new(&g, |_u, _v, w| w as i64 * 2);
+        let lhs: i32 = dijkstra::dijkstra(&g, 0).map(|e| e.dist).sum();
+        let rhs: i64 = dijkstra::dijkstra(&g2, 0).map(|e| e.dist).sum();
+        assert_eq!(lhs as i64 * 2, rhs);
+    }
+}
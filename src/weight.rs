@@ -32,10 +32,86 @@ pub trait Weight:
     fn min(self, other: Self) -> Self {
 	if self > other { other } else { self }
     }
+
+    /// a value no less than any reachable distance,
+    /// so we can use it as the init value of a minimum, rather than Option
+    fn infinity() -> Self;
+
+    fn is_infinity(&self) -> bool {
+	*self == Self::infinity()
+    }
+
+    /// None if overflowed
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+	Some(self + rhs)
+    }
+
+    /// None if overflowed
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+	Some(self - rhs)
+    }
+
+    /// infinity if overflowed, either upward or downward (a distance can not be "very negative"),
+    /// use checked_add to tell an overflow from a real infinity
+    fn saturating_add(self, rhs: Self) -> Self {
+	self.checked_add(rhs).unwrap_or_else(Self::infinity)
+    }
+}
+
+/// records the candidates `d + w` which overflowed when relaxing edges,
+/// a candidate never beats a real distance if it overflowed upward, so it's only ignored,
+/// and we report Overflow only if some final distance overflowed:
+/// a vertex is reached by nothing but such candidates, or a candidate overflowed downward
+pub(crate) struct OverflowTracker {
+    above: Vec<bool>,
+    below: bool,
 }
+
+impl OverflowTracker {
+    /// `n`: the number of distances to track
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            above: vec![false; n],
+            below: false,
+        }
+    }
+
+    /// Some(d + w) as the candidate distance of `v`, None (and recorded) if overflowed
+    pub(crate) fn add<W: Weight>(&mut self, d: W, w: W, v: usize) -> Option<W> {
+        let res = d.checked_add(w);
+        if res.is_none() {
+            self.record(v, w.is_negative());
+        }
+        res
+    }
+
+    /// a candidate of `v` overflowed, below: downward
+    pub(crate) fn record(&mut self, v: usize, below: bool) {
+        if below {
+            self.below = true;
+        } else {
+            self.above[v] = true;
+        }
+    }
+
+    /// `reached(v)`: v got a distance which did not overflow
+    pub(crate) fn overflowed<F: Fn(usize) -> bool>(&self, reached: F) -> bool {
+        self.below || self.above.iter().enumerate().any(|(v, &x)| x && !reached(v))
+    }
+}
+
+/// some row of dist overflowed, every row has its own tracker
+pub(crate) fn overflowed_rows<T>(trackers: &[OverflowTracker], dist: &[Vec<Option<T>>]) -> bool {
+    trackers
+        .iter()
+        .zip(dist)
+        .any(|(tracker, dist_u)| tracker.overflowed(|v| dist_u[v].is_some()))
+}
+
 /// since we can not implement Add, Sub trait for ()
 /// I implement a zero sized type (i.e. NoWeight) by myself
 #[derive(Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoWeight;
 
 impl NoWeight {
@@ -60,7 +136,7 @@ impl Sub for NoWeight {
     }
 }
 
-/// did not implement Neg implemented (u8, u16,...)
+// did not implement Neg implemented (u8, u16,...)
 // impl Neg for NoWeight {
 //     type Output = Self;
 
@@ -89,18 +165,93 @@ impl fmt::Debug for NoWeight {
     }
 }
 
-impl Weight for i8 {}
-impl Weight for u8 {}
-impl Weight for i16 {}
-impl Weight for u16 {}
-impl Weight for i32 {}
-impl Weight for u32 {}
-impl Weight for i64 {}
-impl Weight for u64 {}
-impl Weight for i128 {}
-impl Weight for u128 {}
-impl Weight for isize {}
-impl Weight for usize {}
-impl Weight for f32 {}
-impl Weight for f64 {}
-impl Weight for NoWeight {}
+/// integers: infinity is MAX, use their own checked_add/checked_sub
+macro_rules! impl_weight_int {
+    ($($t:ty),+) => {
+        $(
+            impl Weight for $t {
+                fn infinity() -> Self {
+                    <$t>::MAX
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+/// floats: only overflowed if two finite numbers add up to an infinite one
+macro_rules! impl_weight_float {
+    ($($t:ty),+) => {
+        $(
+            impl Weight for $t {
+                fn infinity() -> Self {
+                    <$t>::INFINITY
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    let res = self + rhs;
+                    if res.is_infinite() && self.is_finite() && rhs.is_finite() {
+                        None
+                    } else {
+                        Some(res)
+                    }
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    Weight::checked_add(self, -rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_weight_int!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+impl_weight_float!(f32, f64);
+
+impl Weight for NoWeight {
+    fn infinity() -> Self {
+        Self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Weight::checked_add(200u8, 55), Some(255));
+        assert_eq!(Weight::checked_add(200u8, 56), None);
+        assert_eq!(Weight::saturating_add(200u8, 56), u8::infinity());
+        assert_eq!(Weight::checked_add(i32::MAX, 1), None);
+        assert_eq!(Weight::checked_add(i32::MIN, -1), None);
+        assert_eq!(Weight::checked_sub(0i8, i8::MIN), None);
+        assert_eq!(Weight::checked_sub(-1i8, i8::MIN), Some(i8::MAX));
+        assert_eq!(Weight::saturating_add(i32::MIN, -1), i32::infinity()); // not MIN
+        assert_eq!(Weight::saturating_add(-100i8, -27), -127);
+        assert_eq!(Weight::checked_add(u64::MAX - 1, 1), Some(u64::infinity()));
+        assert!(i64::infinity().is_infinity());
+        assert!(!0usize.is_infinity());
+
+        assert_eq!(Weight::checked_add(f64::MAX, f64::MAX), None);
+        assert_eq!(Weight::checked_add(f64::INFINITY, 1.0), Some(f64::INFINITY));
+        assert_eq!(Weight::saturating_add(f32::MAX, f32::MAX), f32::infinity());
+        assert_eq!(Weight::saturating_add(f64::MIN, f64::MIN), f64::infinity());
+        assert_eq!(NoWeight::infinity().checked_add(NoWeight), Some(NoWeight));
+
+        let mut tracker = OverflowTracker::new(3);
+        assert_eq!(tracker.add(200u8, 55, 1), Some(255));
+        assert_eq!(tracker.add(200u8, 56, 2), None);
+        assert!(!tracker.overflowed(|v| v == 2)); // 2 is reached by another path
+        assert!(tracker.overflowed(|v| v != 2));
+        assert_eq!(tracker.add(-100i8, -100, 0), None);
+        assert!(tracker.overflowed(|_| true));
+    }
+}